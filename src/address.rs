@@ -0,0 +1,88 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Signer},
+};
+
+/// A pubkey argument: a base58 address, the pubkey of a keypair file, or a label from the
+/// address book in the config file. Labels are resolved with [`crate::config::Config::pubkey`],
+/// once the config file is known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Address {
+    Pubkey(Pubkey),
+    Label(String),
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Pubkey(pubkey) => pubkey.fmt(f),
+            Address::Label(label) => f.write_str(label),
+        }
+    }
+}
+
+/// Value parser shared by every pubkey argument. Tries, in order: a base58 pubkey, a keypair
+/// file, an address book label.
+pub fn parse_address(value: &str) -> Result<Address, String> {
+    if let Some(pubkey) = parse_pubkey_or_keypair_file(value)? {
+        return Ok(Address::Pubkey(pubkey));
+    }
+    if is_label(value) {
+        return Ok(Address::Label(value.to_string()));
+    }
+    Err("expected a base58 pubkey, a keypair file or an address book label".to_string())
+}
+
+/// Like [`parse_address`], without labels: for the addresses stored in the address book.
+pub fn parse_pubkey(value: &str) -> Result<Pubkey, String> {
+    parse_pubkey_or_keypair_file(value)?
+        .ok_or_else(|| "expected a base58 pubkey or a keypair file".to_string())
+}
+
+/// `None` when `value` is neither a pubkey nor the path of a file.
+fn parse_pubkey_or_keypair_file(value: &str) -> Result<Option<Pubkey>, String> {
+    if let Ok(pubkey) = Pubkey::from_str(value) {
+        return Ok(Some(pubkey));
+    }
+    if Path::new(value).is_file() {
+        return read_keypair_file(value)
+            .map(|keypair| Some(keypair.pubkey()))
+            .map_err(|err| format!("not a keypair file: {err}"));
+    }
+    if looks_like_path(value) {
+        return Err("no such keypair file".to_string());
+    }
+    // A string of base58 characters as long as an address is a mistyped address, not a label.
+    if (32..=44).contains(&value.len()) && bs58::decode(value).into_vec().is_ok() {
+        return Err("invalid pubkey".to_string());
+    }
+    Ok(None)
+}
+
+fn looks_like_path(value: &str) -> bool {
+    value.contains(std::path::MAIN_SEPARATOR) || value.contains('/') || value.ends_with(".json")
+}
+
+/// Value parser for a new address book label, which must not read as an address itself.
+pub fn parse_label(value: &str) -> Result<String, String> {
+    match parse_address(value) {
+        Ok(Address::Label(label)) => Ok(label),
+        _ => Err(
+            "expected letters, digits, '-' and '_', starting with a letter, that are \
+             neither a pubkey nor a file"
+                .to_string(),
+        ),
+    }
+}
+
+/// Labels start with a letter, followed by letters, digits, `-` and `_`, e.g. `treasury` or
+/// `ops-multisig`.
+fn is_label(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
@@ -0,0 +1,551 @@
+// ─────────────────────────────
+// Solana SDK & Programs
+// ─────────────────────────────
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_instruction::Instruction;
+use solana_loader_v3_interface::instruction::{set_buffer_authority, upgrade};
+use solana_message::AddressLookupTableAccount;
+use solana_sdk::{
+    clock::Clock,
+    message::{v0::Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::VersionedTransaction,
+};
+use solana_sdk_ids::sysvar::clock;
+use solana_system_interface::program::ID as SYS_PROGRAM_ID;
+
+// SPL
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+// ─────────────────────────────
+// Fortis SDK
+// ─────────────────────────────
+use fortis_sdk::{
+    client::{
+        multisig_create, proposal_accounts_close, proposal_approve, proposal_create,
+        proposal_execute, PROPOSAL_HEADER_SIZE,
+    },
+    pda::{get_multisig_pda, get_proposal_pda, get_transaction_pda, get_vault_pda, TREASURY},
+    state::{
+        Multisig, MultisigCreateAccounts, MultisigCreateArgs, Proposal,
+        ProposalAccountsCloseAccounts, ProposalApproveAccounts, ProposalApproveArgs,
+        ProposalCreateAccounts, ProposalExecuteAccounts, VaultTransaction, VaultTransactionMessage,
+    },
+};
+
+// ─────────────────────────────
+// Local utilities
+// ─────────────────────────────
+use crate::error::Error;
+use crate::journal::Journal;
+use crate::utils::{
+    get_account_data, get_blockhash, get_necessary_alt, is_index_taken, send_and_confirm_quietly,
+    unique_signers, MAX_INDEX_RETRIES,
+};
+
+/// A multisig created by [`FortisClient::create_multisig`].
+#[derive(Debug, Clone)]
+pub struct MultisigCreated {
+    pub signature: Signature,
+    pub multisig: Pubkey,
+    /// The vault holding the multisig funds.
+    pub vault: Pubkey,
+}
+
+/// A transaction sent on a proposal: created, approved, executed or closed.
+#[derive(Debug, Clone)]
+pub struct ProposalSent {
+    pub signature: Signature,
+    pub multisig: Pubkey,
+    pub transaction_index: u64,
+    pub proposal: Pubkey,
+    pub transaction: Pubkey,
+}
+
+/// Everything known about a proposal: its votes, the vault transaction it would execute and the
+/// lookup tables that transaction refers to.
+pub struct ProposalState {
+    pub multisig: Multisig,
+    pub proposal: Proposal,
+    pub transaction: VaultTransaction,
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+/// Client for the Fortis program, for Rust services that create and vote on proposals without
+/// going through the CLI.
+///
+/// The `*_instruction` and `*_message` methods only build, so they can be sent with any
+/// transaction pipeline; the CLI adds its review screen, compute budget and journal on top of
+/// them. The other async methods send a transaction paid by the given signer and wait for its
+/// confirmation.
+pub struct FortisClient {
+    rpc_client: RpcClient,
+    program_id: Pubkey,
+}
+
+impl FortisClient {
+    pub fn new(rpc_client: RpcClient, program_id: Pubkey) -> Self {
+        Self {
+            rpc_client,
+            program_id,
+        }
+    }
+
+    pub fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_client
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    pub fn vault(&self, multisig: &Pubkey) -> Pubkey {
+        get_vault_pda(multisig, Some(&self.program_id)).0
+    }
+
+    pub fn proposal_address(&self, multisig: &Pubkey, transaction_index: u64) -> Pubkey {
+        get_proposal_pda(multisig, transaction_index, Some(&self.program_id)).0
+    }
+
+    pub fn transaction_address(&self, multisig: &Pubkey, transaction_index: u64) -> Pubkey {
+        get_transaction_pda(multisig, transaction_index, Some(&self.program_id)).0
+    }
+
+    // ─────────────────────────────
+    // State
+    // ─────────────────────────────
+
+    pub async fn multisig(&self, multisig: &Pubkey) -> eyre::Result<Multisig> {
+        let data = get_account_data(&self.rpc_client, multisig, "Multisig").await?;
+        Ok(borsh::from_slice(&data).map_err(|err| {
+            Error::Deserialization(format!("Invalid multisig account {}: {}", multisig, err))
+        })?)
+    }
+
+    /// Unix time on the cluster clock, which the program checks voting deadlines against.
+    pub async fn cluster_time(&self) -> eyre::Result<i64> {
+        let data = get_account_data(&self.rpc_client, &clock::ID, "Clock sysvar").await?;
+        let clock: Clock = bincode::deserialize(&data)
+            .map_err(|err| Error::Deserialization(format!("Invalid clock sysvar: {err}")))?;
+        Ok(clock.unix_timestamp)
+    }
+
+    /// Index the next proposal of `multisig` will get.
+    pub async fn next_transaction_index(&self, multisig: &Pubkey) -> eyre::Result<u64> {
+        Ok(self.multisig(multisig).await?.transaction_index + 1)
+    }
+
+    pub async fn proposal(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> eyre::Result<Proposal> {
+        let proposal = self.proposal_address(multisig, transaction_index);
+        let data = get_account_data(&self.rpc_client, &proposal, "Proposal").await?;
+        Ok(decode_proposal(&proposal, &data)?)
+    }
+
+    pub async fn vault_transaction(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> eyre::Result<VaultTransaction> {
+        let transaction = self.transaction_address(multisig, transaction_index);
+        let data = get_account_data(&self.rpc_client, &transaction, "Transaction").await?;
+        Ok(borsh::from_slice(&data).map_err(|err| {
+            Error::Deserialization(format!(
+                "Invalid transaction account {}: {}",
+                transaction, err
+            ))
+        })?)
+    }
+
+    pub async fn proposal_state(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> eyre::Result<ProposalState> {
+        let multisig_data = self.multisig(multisig).await?;
+        let proposal = self.proposal(multisig, transaction_index).await?;
+        let transaction = self.vault_transaction(multisig, transaction_index).await?;
+        let lookup_tables = get_necessary_alt(&self.rpc_client, &transaction.message).await?;
+        Ok(ProposalState {
+            multisig: multisig_data,
+            proposal,
+            transaction,
+            lookup_tables,
+        })
+    }
+
+    // ─────────────────────────────
+    // Instructions and vault messages
+    // ─────────────────────────────
+
+    /// Creates the multisig derived from `create_key`, which must sign the transaction. Returns
+    /// the instruction and the multisig address.
+    pub fn multisig_create_instruction(
+        &self,
+        creator: &Pubkey,
+        create_key: &Pubkey,
+        members: Vec<Pubkey>,
+        threshold: u16,
+        rent_collector: Option<Pubkey>,
+    ) -> (Instruction, Pubkey) {
+        let multisig = get_multisig_pda(create_key, Some(&self.program_id)).0;
+        let instruction = multisig_create(
+            MultisigCreateAccounts {
+                create_key: *create_key,
+                creator: *creator,
+                multisig,
+                system_program: SYS_PROGRAM_ID,
+                treasury: TREASURY,
+            },
+            MultisigCreateArgs {
+                members,
+                threshold,
+                rent_collector,
+            },
+            Some(self.program_id),
+        );
+        (instruction, multisig)
+    }
+
+    /// Vault message transferring `amount` base units of `mint` from the vault token account to
+    /// the one of `recipient`.
+    pub fn transfer_message(
+        &self,
+        multisig: &Pubkey,
+        token_program: &Pubkey,
+        mint: &Pubkey,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> eyre::Result<VaultTransactionMessage> {
+        let vault = self.vault(multisig);
+        let sender_ata = get_associated_token_address_with_program_id(&vault, mint, token_program);
+        let recipient_ata =
+            get_associated_token_address_with_program_id(recipient, mint, token_program);
+        let transfer = spl_token::instruction::transfer(
+            token_program,
+            &sender_ata,
+            &recipient_ata,
+            &vault,
+            &[&vault],
+            amount,
+        )?;
+        Ok(VaultTransactionMessage::try_compile(
+            &vault,
+            &[transfer],
+            &[],
+        )?)
+    }
+
+    /// Vault message transferring `lamports` from the vault to `recipient`.
+    pub fn native_transfer_message(
+        &self,
+        multisig: &Pubkey,
+        recipient: &Pubkey,
+        lamports: u64,
+    ) -> eyre::Result<VaultTransactionMessage> {
+        let vault = self.vault(multisig);
+        let transfer = solana_system_interface::instruction::transfer(&vault, recipient, lamports);
+        Ok(VaultTransactionMessage::try_compile(
+            &vault,
+            &[transfer],
+            &[],
+        )?)
+    }
+
+    /// Vault message handing `buffer` over from `buffer_authority` to the vault, then upgrading
+    /// `program` from it. The buffer lamports go to `spill`.
+    pub fn program_upgrade_message(
+        &self,
+        multisig: &Pubkey,
+        program: &Pubkey,
+        buffer: &Pubkey,
+        buffer_authority: &Pubkey,
+        spill: &Pubkey,
+    ) -> eyre::Result<VaultTransactionMessage> {
+        let vault = self.vault(multisig);
+        Ok(VaultTransactionMessage::try_compile(
+            &vault,
+            &[
+                set_buffer_authority(buffer, buffer_authority, &vault),
+                upgrade(program, buffer, &vault, spill),
+            ],
+            &[],
+        )?)
+    }
+
+    /// Proposes `message` as transaction `transaction_index` of `multisig`, open for votes until
+    /// `voting_deadline` (unix seconds).
+    pub fn proposal_create_instruction(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        creator: &Pubkey,
+        message: &VaultTransactionMessage,
+        voting_deadline: i64,
+    ) -> Instruction {
+        proposal_create(
+            ProposalCreateAccounts {
+                multisig: *multisig,
+                trasaction: self.transaction_address(multisig, transaction_index),
+                creator: *creator,
+                proposal: self.proposal_address(multisig, transaction_index),
+                system_program: SYS_PROGRAM_ID,
+            },
+            0,
+            message,
+            voting_deadline,
+            Some(self.program_id),
+        )
+    }
+
+    pub fn proposal_approve_instruction(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        member: &Pubkey,
+    ) -> Instruction {
+        proposal_approve(
+            ProposalApproveAccounts {
+                multisig: *multisig,
+                proposal: self.proposal_address(multisig, transaction_index),
+                member: *member,
+            },
+            ProposalApproveArgs {},
+            Some(self.program_id),
+        )
+    }
+
+    /// Reads the vault transaction to list the accounts its instructions need. Returns the
+    /// instruction and the lookup tables to compile the message with.
+    pub async fn proposal_execute_instruction(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        member: &Pubkey,
+    ) -> eyre::Result<(Instruction, Vec<AddressLookupTableAccount>)> {
+        let transaction = self.transaction_address(multisig, transaction_index);
+        let transaction_account_data =
+            get_account_data(&self.rpc_client, &transaction, "Transaction").await?;
+
+        let vault_transaction: VaultTransaction = borsh::from_slice(&transaction_account_data)
+            .map_err(|err| {
+                Error::Deserialization(format!(
+                    "Invalid transaction account {}: {}",
+                    transaction, err
+                ))
+            })?;
+
+        let lookup_tables = get_necessary_alt(&self.rpc_client, &vault_transaction.message).await?;
+
+        let instruction = proposal_execute(
+            &transaction_account_data,
+            ProposalExecuteAccounts {
+                member: *member,
+                multisig: *multisig,
+                proposal: self.proposal_address(multisig, transaction_index),
+                transaction,
+            },
+            &lookup_tables,
+            Some(self.program_id),
+        )
+        .await?;
+
+        Ok((instruction, lookup_tables))
+    }
+
+    /// `rent_collector` must be the one set on the multisig, and signs the transaction.
+    pub fn proposal_accounts_close_instruction(
+        &self,
+        multisig: &Pubkey,
+        transaction_index: u64,
+        rent_collector: &Pubkey,
+    ) -> Instruction {
+        proposal_accounts_close(
+            ProposalAccountsCloseAccounts {
+                multisig: *multisig,
+                proposal: self.proposal_address(multisig, transaction_index),
+                transaction: self.transaction_address(multisig, transaction_index),
+                rent_collector: *rent_collector,
+                system_program: SYS_PROGRAM_ID,
+            },
+            Some(self.program_id),
+        )
+    }
+
+    // ─────────────────────────────
+    // Transactions
+    // ─────────────────────────────
+
+    pub async fn create_multisig(
+        &self,
+        creator: &dyn Signer,
+        members: Vec<Pubkey>,
+        threshold: u16,
+        rent_collector: Option<Pubkey>,
+    ) -> eyre::Result<MultisigCreated> {
+        let create_key = Keypair::new();
+        let (instruction, multisig) = self.multisig_create_instruction(
+            &creator.pubkey(),
+            &create_key.pubkey(),
+            members,
+            threshold,
+            rent_collector,
+        );
+        let signature = self
+            .send(creator, &[&create_key], &[instruction], &[])
+            .await?;
+        Ok(MultisigCreated {
+            signature,
+            multisig,
+            vault: self.vault(&multisig),
+        })
+    }
+
+    /// Proposes `message` with the next transaction index. When another member takes that index
+    /// first, the proposal is sent again with the following one.
+    pub async fn create_proposal(
+        &self,
+        creator: &dyn Signer,
+        multisig: &Pubkey,
+        message: &VaultTransactionMessage,
+        voting_deadline: i64,
+    ) -> eyre::Result<ProposalSent> {
+        let mut retries = 0;
+        loop {
+            let transaction_index = self.next_transaction_index(multisig).await?;
+            let instruction = self.proposal_create_instruction(
+                multisig,
+                transaction_index,
+                &creator.pubkey(),
+                message,
+                voting_deadline,
+            );
+            match self.send(creator, &[], &[instruction], &[]).await {
+                Ok(signature) => return Ok(self.sent(signature, multisig, transaction_index)),
+                Err(_)
+                    if retries < MAX_INDEX_RETRIES
+                        && is_index_taken(
+                            &self.rpc_client,
+                            multisig,
+                            transaction_index,
+                            &creator.pubkey(),
+                            &self.program_id,
+                        )
+                        .await =>
+                {
+                    retries += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    pub async fn approve(
+        &self,
+        member: &dyn Signer,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> eyre::Result<ProposalSent> {
+        let instruction =
+            self.proposal_approve_instruction(multisig, transaction_index, &member.pubkey());
+        let signature = self.send(member, &[], &[instruction], &[]).await?;
+        Ok(self.sent(signature, multisig, transaction_index))
+    }
+
+    pub async fn execute(
+        &self,
+        member: &dyn Signer,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> eyre::Result<ProposalSent> {
+        let (instruction, lookup_tables) = self
+            .proposal_execute_instruction(multisig, transaction_index, &member.pubkey())
+            .await?;
+        let signature = self
+            .send(member, &[], &[instruction], &lookup_tables)
+            .await?;
+        Ok(self.sent(signature, multisig, transaction_index))
+    }
+
+    /// Closes the proposal and transaction accounts, sending their rent to `rent_collector`,
+    /// which signs and pays for the transaction.
+    pub async fn close(
+        &self,
+        rent_collector: &dyn Signer,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> eyre::Result<ProposalSent> {
+        let instruction = self.proposal_accounts_close_instruction(
+            multisig,
+            transaction_index,
+            &rent_collector.pubkey(),
+        );
+        let signature = self.send(rent_collector, &[], &[instruction], &[]).await?;
+        Ok(self.sent(signature, multisig, transaction_index))
+    }
+
+    /// Signs `instructions` with `payer` and `signers` and waits for the confirmation, the way
+    /// the CLI sends: rebroadcast while pending, re-signed when the blockhash expires, and
+    /// failures returned as [`Error::Simulation`], [`Error::Program`] or [`Error::Rpc`] with the
+    /// program error explained.
+    async fn send(
+        &self,
+        payer: &dyn Signer,
+        signers: &[&dyn Signer],
+        instructions: &[Instruction],
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> eyre::Result<Signature> {
+        let blockhash = get_blockhash(&self.rpc_client, None, None).await?;
+        let message =
+            Message::try_compile(&payer.pubkey(), instructions, lookup_tables, blockhash)?;
+        let signers = unique_signers(
+            std::iter::once(Some(payer)).chain(signers.iter().map(|signer| Some(*signer))),
+        );
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &signers)?;
+        send_and_confirm_quietly(
+            &transaction,
+            &self.rpc_client,
+            &signers,
+            &Journal::disabled(),
+            &self.program_id,
+        )
+        .await
+    }
+
+    fn sent(
+        &self,
+        signature: Signature,
+        multisig: &Pubkey,
+        transaction_index: u64,
+    ) -> ProposalSent {
+        ProposalSent {
+            signature,
+            multisig: *multisig,
+            transaction_index,
+            proposal: self.proposal_address(multisig, transaction_index),
+            transaction: self.transaction_address(multisig, transaction_index),
+        }
+    }
+}
+
+/// Decodes a proposal account, whose approvers are followed by unused space. The length is
+/// checked first: `fortis_sdk::client::get_proposal` slices the data unchecked and panics on a
+/// short account.
+fn decode_proposal(address: &Pubkey, data: &[u8]) -> Result<Proposal, Error> {
+    let invalid = |reason: String| {
+        Error::Deserialization(format!("Invalid proposal account {}: {}", address, reason))
+    };
+    let too_short = || invalid(format!("{} bytes is too short", data.len()));
+    let approvers = data
+        .get(PROPOSAL_HEADER_SIZE..PROPOSAL_HEADER_SIZE + 4)
+        .ok_or_else(too_short)?;
+    let approvers = u32::from_le_bytes(approvers.try_into().expect("slice of 4 bytes")) as usize;
+    let proposal = data
+        .get(..PROPOSAL_HEADER_SIZE + 4 + approvers * 32)
+        .ok_or_else(too_short)?;
+    borsh::from_slice(proposal).map_err(|err| invalid(err.to_string()))
+}
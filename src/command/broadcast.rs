@@ -0,0 +1,175 @@
+// ─────────────────────────────
+// Standard library
+// ─────────────────────────────
+use std::str::FromStr;
+
+// ─────────────────────────────
+// CLI / UX
+// ─────────────────────────────
+use clap::Args;
+use colored::Colorize;
+
+// ─────────────────────────────
+// Solana SDK
+// ─────────────────────────────
+use solana_sdk::{
+    message::VersionedMessage, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+// ─────────────────────────────
+// Local utilities
+// ─────────────────────────────
+use crate::config::Config;
+use crate::decode::find_fortis_instructions;
+use crate::error::Error;
+use crate::info;
+use crate::journal::Journal;
+use crate::output::{self, TransactionOutput};
+use crate::utils::{confirm, send_and_confirm_transaction};
+
+/// Submits a transaction prepared with `--sign-only`, once every signature is collected.
+#[derive(Args)]
+pub struct Broadcast {
+    /// RPC URL. Takes precedence over --url, the profile and the Solana CLI config
+    #[arg(long)]
+    rpc_url: Option<String>,
+
+    /// Message printed by `--sign-only` (base58)
+    #[arg(long)]
+    message: String,
+
+    /// Signature collected from a signer, as PUBKEY=SIGNATURE. Repeat for every signer
+    #[arg(long = "signer", value_name = "PUBKEY=SIGNATURE", value_parser = parse_signer)]
+    signers: Vec<(Pubkey, Signature)>,
+}
+
+impl Broadcast {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
+        let Self {
+            rpc_url,
+            message,
+            signers,
+        } = self;
+
+        let program_id = config.program_id();
+
+        let rpc_url = config.rpc_url(rpc_url)?;
+
+        let message_bytes = bs58::decode(&message)
+            .into_vec()
+            .map_err(|_| Error::InvalidInput("Invalid message: not base58".to_string()))?;
+        let message: VersionedMessage = bincode::deserialize(&message_bytes).map_err(|_| {
+            Error::Deserialization("Invalid message: could not deserialize".to_string())
+        })?;
+
+        // The header comes from the user: check it before slicing the keys with it.
+        let num_required_signatures = message.header().num_required_signatures as usize;
+        let num_keys = message.static_account_keys().len();
+        if num_required_signatures == 0 {
+            return Err(Error::InvalidInput(
+                "Invalid message: it requires no signature, so it has no fee payer".to_string(),
+            )
+            .into());
+        }
+        if num_required_signatures > num_keys {
+            return Err(Error::InvalidInput(format!(
+                "Invalid message: it requires {num_required_signatures} signatures but has only {num_keys} account keys"
+            ))
+            .into());
+        }
+        message
+            .sanitize()
+            .map_err(|err| Error::InvalidInput(format!("Invalid message: {err}")))?;
+        let signer_keys = &message.static_account_keys()[..num_required_signatures];
+        let fee_payer = signer_keys[0];
+
+        let mut signatures = Vec::with_capacity(signer_keys.len());
+        let mut missing = Vec::new();
+        for key in signer_keys {
+            match signers.iter().find(|(signer, _)| signer == key) {
+                Some((_, signature)) => {
+                    if !signature.verify(key.as_ref(), &message_bytes) {
+                        return Err(Error::InvalidInput(format!(
+                            "Signature for {} does not match the message",
+                            key
+                        ))
+                        .into());
+                    }
+                    signatures.push(*signature);
+                }
+                None => missing.push(key.to_string()),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "Missing signatures for: {}",
+                missing.join(", ")
+            ))
+            .into());
+        }
+
+        let multisig = find_fortis_instructions(&message, &program_id)
+            .into_iter()
+            .find_map(|ix| ix.multisig);
+
+        info!();
+        info!(
+            "{}",
+            "👀 You're about to broadcast a signed transaction, please review the details:"
+                .yellow()
+        );
+        info!();
+        info!("RPC Cluster URL:   {}", rpc_url);
+        info!("Program ID:        {}", program_id);
+        info!("Fee Payer:         {}", fee_payer);
+        info!("Blockhash:         {}", message.recent_blockhash());
+        info!(
+            "Multisig Key:      {}",
+            multisig.map_or("unknown".to_string(), |multisig| multisig.to_string())
+        );
+        info!("Signers:           {}", signer_keys.len());
+        info!();
+
+        confirm(config)?;
+        info!();
+
+        let rpc_client = config.rpc_client(rpc_url);
+
+        let transaction = VersionedTransaction {
+            signatures,
+            message,
+        };
+
+        let journal = Journal::new(config, "broadcast", multisig, None);
+        let signature =
+            send_and_confirm_transaction(&transaction, &rpc_client, &[], &journal, &program_id)
+                .await?;
+
+        output::print(
+            &TransactionOutput {
+                signature,
+                multisig: multisig.map(|multisig| multisig.to_string()),
+                transaction_index: None,
+                proposal: None,
+                transaction: None,
+            },
+            |result| {
+                println!(
+                    "✅ Broadcasted transaction. Signature: {}",
+                    result.signature.green()
+                )
+            },
+        )
+    }
+}
+
+fn parse_signer(value: &str) -> Result<(Pubkey, Signature), String> {
+    let (pubkey, signature) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Expected PUBKEY=SIGNATURE, got {value}"))?;
+    let pubkey = Pubkey::from_str(pubkey).map_err(|_| format!("Invalid public key: {pubkey}"))?;
+    let signature =
+        Signature::from_str(signature).map_err(|_| format!("Invalid signature: {signature}"))?;
+    Ok((pubkey, signature))
+}
@@ -0,0 +1,140 @@
+use clap::Subcommand;
+use colored::Colorize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::address;
+use crate::config::{Config, ConfigFile, PROFILE_KEYS};
+use crate::error::Error;
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print a value from the selected profile
+    Get {
+        /// One of rpc_url, commitment, keypair, multisig, priority_fee, compute_unit_margin,
+        /// program_id
+        key: String,
+    },
+    /// Set a value on the selected profile, creating the profile if needed
+    Set {
+        /// One of rpc_url, commitment, keypair, multisig, priority_fee, compute_unit_margin,
+        /// program_id
+        key: String,
+        value: String,
+    },
+    /// List all profiles and their values
+    List,
+    /// Manage the labels that pubkey arguments accept in place of an address
+    #[command(subcommand)]
+    AddressBook(AddressBookCommand),
+}
+
+#[derive(Subcommand)]
+pub enum AddressBookCommand {
+    /// Add a label, or point an existing one at another address
+    Set {
+        /// Letters, digits, `-` and `_`, starting with a letter
+        #[arg(value_parser = address::parse_label)]
+        label: String,
+        /// Base58 pubkey, or a keypair file to take the pubkey of
+        #[arg(value_parser = address::parse_pubkey)]
+        address: Pubkey,
+    },
+    /// Remove a label
+    Remove {
+        /// Label to remove
+        label: String,
+    },
+    /// List the labels and their addresses
+    List,
+}
+
+impl ConfigCommand {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
+        match self {
+            ConfigCommand::Get { key } => {
+                let value = config.profile.get(&key)?;
+                println!("{}", value.unwrap_or_else(|| "(not set)".to_string()));
+            }
+            ConfigCommand::Set { key, value } => {
+                let mut file = ConfigFile::load(&config.path)?;
+                file.profiles
+                    .entry(config.profile_name.clone())
+                    .or_default()
+                    .set(&key, value)?;
+                file.save(&config.path)?;
+                println!(
+                    "✅ Updated {} in profile {}",
+                    key.bold(),
+                    config.profile_name.green()
+                );
+            }
+            ConfigCommand::List => {
+                let file = ConfigFile::load(&config.path)?;
+                println!(
+                    "{} {}",
+                    "Config file:".dimmed().cyan(),
+                    config.path.display()
+                );
+                if file.profiles.is_empty() {
+                    println!("No profiles configured.");
+                }
+                for (name, profile) in &file.profiles {
+                    let marker = if *name == config.profile_name {
+                        "▶"
+                    } else {
+                        " "
+                    };
+                    println!("\n{} {}", marker.cyan(), name.bold());
+                    for key in PROFILE_KEYS {
+                        let value = profile.get(key)?;
+                        println!(
+                            "  {:<19}: {}",
+                            key,
+                            value.unwrap_or_else(|| "-".to_string())
+                        );
+                    }
+                }
+                if !file.address_book.is_empty() {
+                    println!("\n{}", "Address book".bold());
+                    for (label, address) in &file.address_book {
+                        println!("  {:<19}: {}", label, address);
+                    }
+                }
+            }
+            ConfigCommand::AddressBook(command) => command.execute(config)?,
+        }
+        Ok(())
+    }
+}
+
+impl AddressBookCommand {
+    fn execute(self, config: &Config) -> eyre::Result<()> {
+        let mut file = ConfigFile::load(&config.path)?;
+        match self {
+            AddressBookCommand::Set { label, address } => {
+                file.address_book.insert(label.clone(), address.to_string());
+                file.save(&config.path)?;
+                println!("✅ {} now stands for {}", label.bold(), address);
+            }
+            AddressBookCommand::Remove { label } => {
+                if file.address_book.remove(&label).is_none() {
+                    return Err(Error::InvalidInput(format!(
+                        "Label '{label}' is not in the address book"
+                    ))
+                    .into());
+                }
+                file.save(&config.path)?;
+                println!("✅ Removed {} from the address book", label.bold());
+            }
+            AddressBookCommand::List => {
+                if file.address_book.is_empty() {
+                    println!("The address book is empty.");
+                }
+                for (label, address) in &file.address_book {
+                    println!("{:<20} {}", label, address);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,134 +1,210 @@
 use colored::*;
-use fortis_sdk::{
-    client::{get_multisig, get_proposal},
-    pda::*,
-};
-use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::pubkey::Pubkey;
-use std::str::FromStr;
+use serde::Serialize;
 
 use clap::Args;
 
+use crate::address::{parse_address, Address};
+use crate::config::Config;
+use crate::error::Error;
+use crate::output;
+use crate::utils::{format_deadline, format_timestamp, proposal_status};
+
 #[derive(Args)]
 pub struct DisplayMultisig {
-    /// RPC URL
+    /// RPC URL. Takes precedence over --url, the profile and the Solana CLI config
     #[arg(long)]
     rpc_url: Option<String>,
-    /// Multisig
-    #[arg(long)]
-    multisig_address: String,
+    /// Multisig. Defaults to the profile multisig
+    #[arg(long, alias = "multisig-address", value_parser = parse_address)]
+    multisig_pubkey: Option<Address>,
+}
+
+#[derive(Serialize)]
+struct MultisigOutput {
+    multisig: String,
+    create_key: String,
+    rent_collector: String,
+    transaction_index: u64,
+    threshold: u16,
+    members: Vec<String>,
+    vault: String,
+    proposals: Vec<ProposalOutput>,
 }
+
+#[derive(Serialize)]
+struct ProposalOutput {
+    index: u64,
+    address: String,
+    /// `Active`, `Expired`, `Approved`, `Executed`, or `NotFound` once the accounts are closed.
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deadline: Option<i64>,
+    approved: Vec<String>,
+}
+
 impl DisplayMultisig {
-    pub async fn execute(self) -> eyre::Result<()> {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
         let Self {
             rpc_url,
-            multisig_address,
+            multisig_pubkey,
         } = self;
 
-        let rpc_url = rpc_url.unwrap_or_else(|| "https://api.mainnet-beta.solana.com".to_string());
+        let client = config.client(config.rpc_url(rpc_url)?);
 
-        let rpc_client = &RpcClient::new(rpc_url);
+        let multisig = config.multisig(multisig_pubkey)?;
+        let multisig_data = client.multisig(&multisig).await?;
+        let vault_address = client.vault(&multisig);
+        let now = client.cluster_time().await?;
 
-        let multisig = Pubkey::from_str(&multisig_address).expect("Invalid multisig address");
-        let multisig_data = get_multisig(rpc_client, &multisig).await?;
-        let multisig_address =
-            Pubkey::from_str(&multisig_address).expect("Invalid multisig address");
-        let vault_address = get_vault_pda(&multisig_address, None);
-        println!(
-            "{} {}",
-            "Multisig address:".dimmed().cyan(),
-            multisig_address.to_string().bold()
-        );
-
-        println!(
-            "{} {}",
-            "Multisig creator:".dimmed().cyan(),
-            multisig_data.create_key.to_string().bold()
-        );
-
-        println!(
-            "{} {}",
-            "Rent collector:".dimmed().cyan(),
-            multisig_data.rent_collector.to_string().bold()
-        );
-
-        println!(
-            "{} {}",
-            "Proposals count:".dimmed().cyan(),
-            multisig_data.transaction_index.to_string().bold()
-        );
-
-        println!(
-            "{} {}",
-            "Multisig threshold:".dimmed().cyan(),
-            multisig_data.threshold.to_string().bold()
-        );
-
-        println!(
-            "{}\n{:#?}",
-            "Multisig members:".dimmed().cyan(),
-            multisig_data.members
-        );
-
-        println!(
-            "{} {}",
-            "Multisig vault:".dimmed().cyan(),
-            vault_address.0.to_string().bold()
-        );
-        //display all proposals and transactions
+        //fetch all proposals and transactions
         let num_proposals = multisig_data.transaction_index;
+        let mut proposals = Vec::new();
 
         for proposal_index in 1..num_proposals + 1 {
-            let proposal_key = get_proposal_pda(&multisig_address, proposal_index, None).0;
+            let proposal_key = client.proposal_address(&multisig, proposal_index);
 
-            let proposal = match get_proposal(rpc_client, &proposal_key).await {
+            let proposal = match client.proposal(&multisig, proposal_index).await {
                 Ok(p) => p,
-                Err(_) => {
-                    println!(
-                        "{} Proposal #{} does not exist\n",
-                        "⚠".yellow(),
-                        proposal_index
-                    );
+                // The accounts of a proposal are closed once it is done with.
+                Err(err) if matches!(err.downcast_ref(), Some(Error::AccountNotFound(_))) => {
+                    proposals.push(ProposalOutput {
+                        index: proposal_index,
+                        address: proposal_key.to_string(),
+                        status: "NotFound",
+                        created_at: None,
+                        deadline: None,
+                        approved: Vec::new(),
+                    });
                     continue;
                 }
+                Err(err) => return Err(err),
             };
 
+            proposals.push(ProposalOutput {
+                index: proposal_index,
+                address: proposal_key.to_string(),
+                status: proposal_status(&proposal, now),
+                created_at: Some(proposal.timestamp),
+                deadline: Some(proposal.deadline),
+                approved: proposal.approved.iter().map(|pk| pk.to_string()).collect(),
+            });
+        }
+
+        output::print(
+            &MultisigOutput {
+                multisig: multisig.to_string(),
+                create_key: multisig_data.create_key.to_string(),
+                rent_collector: multisig_data.rent_collector.to_string(),
+                transaction_index: multisig_data.transaction_index,
+                threshold: multisig_data.threshold,
+                members: multisig_data
+                    .members
+                    .iter()
+                    .map(|pk| pk.to_string())
+                    .collect(),
+                vault: vault_address.to_string(),
+                proposals,
+            },
+            |multisig| print_multisig(multisig, now),
+        )
+    }
+}
+
+fn print_multisig(multisig: &MultisigOutput, now: i64) {
+    println!(
+        "{} {}",
+        "Multisig address:".dimmed().cyan(),
+        multisig.multisig.bold()
+    );
+
+    println!(
+        "{} {}",
+        "Multisig creator:".dimmed().cyan(),
+        multisig.create_key.bold()
+    );
+
+    println!(
+        "{} {}",
+        "Rent collector:".dimmed().cyan(),
+        multisig.rent_collector.bold()
+    );
+
+    println!(
+        "{} {}",
+        "Proposals count:".dimmed().cyan(),
+        multisig.transaction_index.to_string().bold()
+    );
+
+    println!(
+        "{} {}",
+        "Multisig threshold:".dimmed().cyan(),
+        multisig.threshold.to_string().bold()
+    );
+
+    println!("{}", "Multisig members:".dimmed().cyan());
+    if multisig.members.is_empty() {
+        println!("[]");
+    } else {
+        println!("[");
+        for member in &multisig.members {
+            println!("    {},", member);
+        }
+        println!("]");
+    }
+
+    println!(
+        "{} {}",
+        "Multisig vault:".dimmed().cyan(),
+        multisig.vault.bold()
+    );
+
+    for proposal in &multisig.proposals {
+        if proposal.status == "NotFound" {
             println!(
-                "\n{} Proposal #{}",
-                "▶".cyan(),
-                proposal_index.to_string().bold()
-            );
-            println!("  Address     : {}", proposal_key.to_string().dimmed());
-            println!("  Created At  : {}", proposal.timestamp);
-            println!("  Deadline    : {}", proposal.deadline);
-            println!(
-                "  Approvals   : {}/{}",
-                proposal.approved.len().to_string().green(),
-                multisig_data.threshold
+                "{} Proposal #{} does not exist\n",
+                "⚠".yellow(),
+                proposal.index
             );
+            continue;
+        }
 
-            if proposal.approved.is_empty() {
-                println!("  Approvers   : {}", "None".dimmed());
-            } else {
-                for pk in &proposal.approved {
-                    println!("    • {}", pk.to_string().bright_white());
-                }
-            }
+        println!(
+            "\n{} Proposal #{}",
+            "▶".cyan(),
+            proposal.index.to_string().bold()
+        );
+        println!("  Address     : {}", proposal.address.dimmed());
+        println!(
+            "  Created At  : {}",
+            format_timestamp(proposal.created_at.unwrap_or_default())
+        );
+        println!(
+            "  Deadline    : {}",
+            format_deadline(proposal.deadline.unwrap_or_default(), now)
+        );
+        println!(
+            "  Approvals   : {}/{}",
+            proposal.approved.len().to_string().green(),
+            multisig.threshold
+        );
 
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
+        if proposal.approved.is_empty() {
+            println!("  Approvers   : {}", "None".dimmed());
+        } else {
+            for pk in &proposal.approved {
+                println!("    • {}", pk.bright_white());
+            }
+        }
 
-            let status = match proposal.status {
-                0 if now > proposal.deadline as u64 => "Expired".red(),
-                0 => "Active".yellow(),
-                1 => "Approved".green(),
-                _ => "Executed".bright_green(),
-            };
+        let status = match proposal.status {
+            "Expired" => proposal.status.red(),
+            "Active" => proposal.status.yellow(),
+            "Approved" => proposal.status.green(),
+            _ => proposal.status.bright_green(),
+        };
 
-            println!("  Status      : {}\n", status.bold());
-        }
-        Ok(())
+        println!("  Status      : {}\n", status.bold());
     }
 }
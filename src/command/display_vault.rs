@@ -1,25 +1,42 @@
 use fortis_sdk::pda::get_vault_pda;
-use solana_sdk::pubkey::Pubkey;
-use std::str::FromStr;
+use serde::Serialize;
 
 use clap::Args;
+
+use crate::address::{parse_address, Address};
+use crate::config::Config;
+use crate::output;
+
 #[derive(Args)]
 pub struct DisplayVault {
-    /// Multisig
-    #[arg(long)]
-    multisig_address: String,
+    /// Multisig. Defaults to the profile multisig
+    #[arg(long, alias = "multisig-address", value_parser = parse_address)]
+    multisig_pubkey: Option<Address>,
+}
+
+#[derive(Serialize)]
+struct VaultOutput {
+    multisig: String,
+    vault: String,
+    bump: u8,
 }
 
 impl DisplayVault {
-    pub async fn execute(self) -> eyre::Result<()> {
-        let Self { multisig_address } = self;
-        let multisig_address =
-            Pubkey::from_str(&multisig_address).expect("Invalid multisig address");
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
+        let Self { multisig_pubkey } = self;
 
-        let vault_address = get_vault_pda(&multisig_address, None);
+        let program_id = config.program_id();
+        let multisig = config.multisig(multisig_pubkey)?;
 
-        println!("Vault: {:?}", vault_address);
+        let vault_address = get_vault_pda(&multisig, Some(&program_id));
 
-        Ok(())
+        output::print(
+            &VaultOutput {
+                multisig: multisig.to_string(),
+                vault: vault_address.0.to_string(),
+                bump: vault_address.1,
+            },
+            |_| println!("Vault: {:?}", vault_address),
+        )
     }
 }
@@ -1,25 +1,66 @@
+use crate::command::broadcast::Broadcast;
+use crate::command::config::ConfigCommand;
 use crate::command::display_multisig::DisplayMultisig;
 use crate::command::display_vault::DisplayVault;
 use crate::command::initiate_native_transfer::InitiateNativeTransfer;
 use crate::command::initiate_program_upgrade::InitiateProgramUpgrade;
 use crate::command::initiate_transfer::InitiateTransfer;
 use crate::command::multisig_create::MultisigCreate;
+use crate::command::nonce::NonceCommand;
+use crate::command::pending::Pending;
 use crate::command::proposal_accounts_close::ProposalAccountsClose;
 use crate::command::proposal_approve::ProposalApprove;
 use crate::command::proposal_create::ProposalCreate;
 use crate::command::proposal_execute::ProposalExecute;
+use crate::command::proposal_show::ProposalShow;
+use crate::command::tx_status::TxStatus;
 
-use clap::Subcommand;
+use clap::{Parser, Subcommand};
+
+use crate::config::{Config, GlobalArgs};
+pub mod broadcast;
+pub mod config;
 pub mod display_multisig;
 pub mod display_vault;
 pub mod initiate_native_transfer;
 pub mod initiate_program_upgrade;
 pub mod initiate_transfer;
 pub mod multisig_create;
+pub mod nonce;
+pub mod pending;
 pub mod proposal_accounts_close;
 pub mod proposal_approve;
 pub mod proposal_create;
 pub mod proposal_execute;
+pub mod proposal_show;
+pub mod tx_status;
+
+/// Flags renamed for consistency, still accepted under their old name as hidden aliases:
+/// `(old, new)`.
+pub const DEPRECATED_FLAGS: &[(&str, &str)] = &[("--multisig-address", "--multisig-pubkey")];
+
+/// Warns on stderr about the deprecated flags among the command line `args`.
+pub fn warn_deprecated_flags(args: &[String]) {
+    for (old, new) in DEPRECATED_FLAGS {
+        let used = args
+            .iter()
+            .any(|arg| arg == old || arg.starts_with(&format!("{old}=")));
+        if used {
+            eprintln!("⚠ {old} is deprecated and will be removed, use {new}");
+        }
+    }
+}
+
+/// The `fortis-multisig-cli` command line: global options and a subcommand.
+#[derive(Parser)]
+#[command(name = "fortis-multisig-cli")]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
 
 #[derive(Subcommand)]
 pub enum Command {
@@ -28,9 +69,46 @@ pub enum Command {
     ProposalExecute(ProposalExecute),
     ProposalCreate(ProposalCreate),
     ProposalAccountsClose(ProposalAccountsClose),
+    /// Show a proposal and decode the instructions it would execute
+    ProposalShow(ProposalShow),
     InitiateTransfer(InitiateTransfer),
     InitiateNativeTransfer(InitiateNativeTransfer),
     InitiateProgramUpgrade(InitiateProgramUpgrade),
     DisplayVault(DisplayVault),
     DisplayMultisig(DisplayMultisig),
+    /// Send a transaction prepared with --sign-only
+    Broadcast(Broadcast),
+    /// Report the outcome of a transaction sent by the CLI, or of any signature
+    TxStatus(TxStatus),
+    /// Check the journaled transactions whose outcome is not known yet
+    Pending(Pending),
+    /// Create and inspect durable nonce accounts
+    #[command(subcommand)]
+    Nonce(NonceCommand),
+    /// Manage profiles in the config file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+impl Command {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
+        match self {
+            Command::MultisigCreate(command) => command.execute(config).await,
+            Command::ProposalApprove(command) => command.execute(config).await,
+            Command::ProposalExecute(command) => command.execute(config).await,
+            Command::ProposalCreate(command) => command.execute(config).await,
+            Command::ProposalAccountsClose(command) => command.execute(config).await,
+            Command::ProposalShow(command) => command.execute(config).await,
+            Command::InitiateTransfer(command) => command.execute(config).await,
+            Command::InitiateNativeTransfer(command) => command.execute(config).await,
+            Command::InitiateProgramUpgrade(command) => command.execute(config).await,
+            Command::DisplayVault(command) => command.execute(config).await,
+            Command::DisplayMultisig(command) => command.execute(config).await,
+            Command::Broadcast(command) => command.execute(config).await,
+            Command::TxStatus(command) => command.execute(config).await,
+            Command::Pending(command) => command.execute(config).await,
+            Command::Nonce(command) => command.execute(config).await,
+            Command::Config(command) => command.execute(config).await,
+        }
+    }
 }
@@ -0,0 +1,206 @@
+// ─────────────────────────────
+// CLI / UX
+// ─────────────────────────────
+use clap::Subcommand;
+use colored::Colorize;
+use serde::Serialize;
+
+// ─────────────────────────────
+// Solana SDK
+// ─────────────────────────────
+use solana_nonce::state::State;
+use solana_sdk::{
+    message::{v0::Message, VersionedMessage},
+    signature::{Keypair, Signer},
+    transaction::VersionedTransaction,
+};
+use solana_system_interface::instruction::create_nonce_account;
+
+// ─────────────────────────────
+// Local utilities
+// ─────────────────────────────
+use crate::address::{parse_address, Address};
+use crate::config::Config;
+use crate::info;
+use crate::journal::Journal;
+use crate::output;
+use crate::utils::{
+    confirm, create_signer_from_path, get_nonce_data, send_and_confirm_transaction,
+};
+
+#[derive(Subcommand)]
+pub enum NonceCommand {
+    /// Create a durable nonce account, by default with the keypair as its authority
+    Create {
+        /// RPC URL. Takes precedence over --url, the profile and the Solana CLI config
+        #[arg(long)]
+        rpc_url: Option<String>,
+
+        /// Path to the keypair paying for the account. Defaults to the profile keypair
+        #[arg(long)]
+        keypair: Option<String>,
+
+        /// Keypair of the new nonce account. A fresh one is generated if omitted
+        #[arg(long)]
+        nonce_keypair: Option<String>,
+
+        /// Authority allowed to advance the nonce. Defaults to the keypair
+        #[arg(long, value_parser = parse_address)]
+        nonce_authority: Option<Address>,
+    },
+    /// Print the authority and current blockhash of a nonce account
+    Show {
+        /// RPC URL. Takes precedence over --url, the profile and the Solana CLI config
+        #[arg(long)]
+        rpc_url: Option<String>,
+
+        /// Address of the nonce account
+        #[arg(value_parser = parse_address)]
+        nonce: Address,
+    },
+}
+
+#[derive(Serialize)]
+struct NonceOutput {
+    nonce: String,
+    authority: String,
+    blockhash: String,
+    lamports_per_signature: u64,
+}
+
+#[derive(Serialize)]
+struct NonceCreateOutput {
+    signature: String,
+    nonce: String,
+}
+
+impl NonceCommand {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
+        match self {
+            NonceCommand::Create {
+                rpc_url,
+                keypair,
+                nonce_keypair,
+                nonce_authority,
+            } => create(config, rpc_url, keypair, nonce_keypair, nonce_authority).await,
+            NonceCommand::Show { rpc_url, nonce } => {
+                let nonce = config.pubkey(&nonce)?;
+                let rpc_client = config.rpc_client(config.rpc_url(rpc_url)?);
+                let data = get_nonce_data(&rpc_client, &nonce).await?;
+
+                output::print(
+                    &NonceOutput {
+                        nonce: nonce.to_string(),
+                        authority: data.authority.to_string(),
+                        blockhash: data.blockhash().to_string(),
+                        lamports_per_signature: data.get_lamports_per_signature(),
+                    },
+                    |nonce| {
+                        println!(
+                            "{} {}",
+                            "Nonce account:".dimmed().cyan(),
+                            nonce.nonce.bold()
+                        );
+                        println!(
+                            "{} {}",
+                            "Authority:".dimmed().cyan(),
+                            nonce.authority.bold()
+                        );
+                        println!(
+                            "{} {}",
+                            "Blockhash:".dimmed().cyan(),
+                            nonce.blockhash.bold()
+                        );
+                        println!(
+                            "{} {}",
+                            "Lamports per signature:".dimmed().cyan(),
+                            nonce.lamports_per_signature.to_string().bold()
+                        );
+                    },
+                )
+            }
+        }
+    }
+}
+
+async fn create(
+    config: &Config,
+    rpc_url: Option<String>,
+    keypair: Option<String>,
+    nonce_keypair: Option<String>,
+    nonce_authority: Option<Address>,
+) -> eyre::Result<()> {
+    let payer_keypair = create_signer_from_path(config.keypair(keypair)?)?;
+    let payer = payer_keypair.pubkey();
+
+    let nonce_keypair = match nonce_keypair {
+        Some(path) => create_signer_from_path(path)?,
+        None => Box::new(Keypair::new()),
+    };
+    let nonce = nonce_keypair.pubkey();
+    let nonce_authority = match nonce_authority {
+        Some(nonce_authority) => config.pubkey(&nonce_authority)?,
+        None => payer,
+    };
+
+    let rpc_url = config.rpc_url(rpc_url)?;
+
+    info!();
+    info!(
+        "{}",
+        "👀 You're about to create a durable nonce account, please review the details:".yellow()
+    );
+    info!();
+    info!("RPC Cluster URL:   {}", rpc_url);
+    info!("Your Public Key:       {}", payer);
+    info!();
+    info!("⚙️ Config Parameters");
+    info!("Nonce Account:       {}", nonce);
+    info!("Nonce Authority:       {}", nonce_authority);
+    info!();
+
+    confirm(config)?;
+    info!();
+
+    let rpc_client = config.rpc_client(rpc_url);
+
+    let lamports = rpc_client
+        .get_minimum_balance_for_rent_exemption(State::size())
+        .await?;
+
+    let blockhash = rpc_client.get_latest_blockhash().await?;
+
+    let message = Message::try_compile(
+        &payer,
+        &create_nonce_account(&payer, &nonce, &nonce_authority, lamports),
+        &[],
+        blockhash,
+    )?;
+
+    let signers = [&*payer_keypair, &*nonce_keypair];
+    let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &signers)?;
+
+    let journal = Journal::new(config, "nonce-create", None, None);
+    let signature = send_and_confirm_transaction(
+        &transaction,
+        &rpc_client,
+        &signers,
+        &journal,
+        &config.program_id(),
+    )
+    .await?;
+
+    output::print(
+        &NonceCreateOutput {
+            signature,
+            nonce: nonce.to_string(),
+        },
+        |result| {
+            println!(
+                "✅ Created nonce account {}. Signature: {}",
+                result.nonce.bold(),
+                result.signature.green()
+            )
+        },
+    )
+}
@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+use clap::Args;
+use colored::*;
+use solana_sdk::signature::Signature;
+
+use crate::command::tx_status::{check_status, print_status, TxStatusOutput};
+use crate::config::Config;
+use crate::journal::{self, Journal, JournalStatus};
+use crate::output;
+
+#[derive(Args)]
+pub struct Pending {
+    /// RPC URL. Defaults to the cluster each transaction was sent to
+    #[arg(long)]
+    rpc_url: Option<String>,
+}
+
+impl Pending {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
+        let entries = journal::load(&Journal::path(config))?;
+
+        let mut statuses = Vec::new();
+        for entry in entries
+            .iter()
+            .filter(|entry| entry.status == JournalStatus::Pending)
+        {
+            let checked = async {
+                let signature = Signature::from_str(&entry.signature)?;
+                let rpc_url =
+                    config.rpc_url(self.rpc_url.clone().or(Some(entry.rpc_url.clone())))?;
+                let rpc_client = config.rpc_client(rpc_url);
+                check_status(config, &rpc_client, &signature, Some(entry)).await
+            };
+            // A cluster that does not answer must not hide the other pending transactions.
+            statuses.push(match checked.await {
+                Ok(status) => status,
+                Err(err) => TxStatusOutput {
+                    error: Some(format!("Status check failed: {err}")),
+                    ..TxStatusOutput::unknown(entry.signature.clone(), Some(entry))
+                },
+            });
+        }
+
+        output::print(&statuses, |statuses| {
+            if statuses.is_empty() {
+                println!("{}", "No pending transactions".dimmed());
+            }
+            for status in statuses {
+                print_status(status);
+            }
+        })
+    }
+}
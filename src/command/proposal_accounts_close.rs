@@ -1,167 +1,108 @@
-// ─────────────────────────────
-// Standard library
-// ─────────────────────────────
-use std::str::FromStr;
-use std::time::Duration;
-
 // ─────────────────────────────
 // CLI / UX
 // ─────────────────────────────
 use clap::Args;
 use colored::Colorize;
-use dialoguer::Confirm;
-use indicatif::ProgressBar;
-
-// ─────────────────────────────
-// Solana SDK
-// ─────────────────────────────
-use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_compute_budget_interface::ComputeBudgetInstruction;
-use solana_sdk::{
-    message::{v0::Message, VersionedMessage},
-    pubkey::Pubkey,
-    signature::Signer,
-    transaction::VersionedTransaction,
-};
-use solana_system_interface::program::ID as SYS_PROGRAM_ID;
-
-// ─────────────────────────────
-// Fortis SDK
-// ─────────────────────────────
-use fortis_sdk::{
-    client::proposal_accounts_close,
-    pda::{get_proposal_pda, get_transaction_pda, FORTIS_PROGRAM_ID},
-    state::ProposalAccountsCloseAccounts,
-};
 
 // ─────────────────────────────
 // Local utilities
 // ─────────────────────────────
-use crate::utils::{create_signer_from_path, send_and_confirm_transaction};
+use crate::address::{parse_address, Address};
+use crate::config::Config;
+use crate::info;
+use crate::output::{self, TransactionOutput};
+use crate::pipeline::{TransactionArgs, TransactionPipeline};
 
 #[derive(Args)]
 pub struct ProposalAccountsClose {
-    /// RPC URL
+    /// RPC URL. Takes precedence over --url, the profile and the Solana CLI config
     #[arg(long)]
     rpc_url: Option<String>,
 
-    /// Path to the Keypair
+    /// Path to the Keypair. Defaults to the profile keypair
     #[arg(long)]
-    keypair: String,
+    keypair: Option<String>,
 
-    /// The multisig key
-    #[arg(long)]
-    multisig_pubkey: String,
+    /// The multisig key. Defaults to the profile multisig
+    #[arg(long, value_parser = parse_address)]
+    multisig_pubkey: Option<Address>,
 
     /// Index of the transaction to vote on
     #[arg(long)]
     transaction_index: u64,
 
     /// The proposal account key
-    #[arg(long)]
-    rent_collector: String,
+    #[arg(long, value_parser = parse_address)]
+    rent_collector: Address,
 
-    #[arg(long)]
-    priority_fee_lamports: Option<u64>,
+    #[command(flatten)]
+    transaction: TransactionArgs,
 }
 
 impl ProposalAccountsClose {
-    pub async fn execute(self) -> eyre::Result<()> {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
         let Self {
             rpc_url,
             keypair,
             multisig_pubkey,
             transaction_index,
             rent_collector,
-            priority_fee_lamports,
+            transaction,
         } = self;
 
-        let multisig = Pubkey::from_str(&multisig_pubkey).expect("Invalid multisig key");
-
-        let proposal_pda = get_proposal_pda(&multisig, transaction_index, None);
+        let pipeline = TransactionPipeline::new(
+            config,
+            "proposal-accounts-close",
+            rpc_url,
+            keypair,
+            transaction,
+        )?;
 
-        let transaction_pda = get_transaction_pda(&multisig, transaction_index, None);
+        let multisig = config.multisig(multisig_pubkey)?;
 
-        let rent_collector_key =
-            Pubkey::from_str(&rent_collector).expect("Invalid rent collector key");
+        let rent_collector_key = config.pubkey(&rent_collector)?;
 
-        let transaction_creator_keypair = create_signer_from_path(keypair).unwrap();
+        let pipeline = pipeline.multisig(multisig);
+        let client = pipeline.client();
 
-        let transaction_creator = transaction_creator_keypair.pubkey();
+        let proposal_pda = client.proposal_address(&multisig, transaction_index);
+        let transaction_pda = client.transaction_address(&multisig, transaction_index);
 
-        let rpc_url = rpc_url.unwrap_or_else(|| "https://api.mainnet-beta.solana.com".to_string());
+        pipeline
+            .print_review("👀 You're about to close proposal accounts, please review the details:");
+        info!();
+        info!("Multisig Key:          {}", multisig);
+        info!("Transaction Index:      {}", transaction_index);
+        info!("Rent reclaimer:      {}", rent_collector_key);
+        info!();
 
-        println!();
-        println!(
-            "{}",
-            "👀 You're about to initialize ProgramConfig, please review the details:".yellow()
+        let instruction = client.proposal_accounts_close_instruction(
+            &multisig,
+            transaction_index,
+            &rent_collector_key,
         );
-        println!();
-        println!("RPC Cluster URL:   {}", rpc_url);
-        println!("Program ID:        {}", FORTIS_PROGRAM_ID.to_string());
-        println!("Initializer:       {}", transaction_creator);
-        println!();
-        println!("⚙️ Config Parameters");
-        println!();
-        println!("Multisig Key:          {}", multisig_pubkey);
-        println!("Transaction Index:      {}", transaction_index);
-        println!("Rent reclamimer:      {}", rent_collector);
-        println!();
-
-        let proceed = Confirm::new()
-            .with_prompt("Do you want to proceed?")
-            .default(false)
-            .interact()?;
-        if !proceed {
-            println!("OK, aborting.");
-            return Ok(());
-        }
-        println!();
-
-        let rpc_client = RpcClient::new(rpc_url);
-
-        let progress = ProgressBar::new_spinner().with_message("Sending transaction...");
-        progress.enable_steady_tick(Duration::from_millis(100));
-
-        let blockhash = rpc_client
-            .get_latest_blockhash()
-            .await
-            .expect("Failed to get blockhash");
-
-        let message = Message::try_compile(
-            &transaction_creator,
-            &[
-                ComputeBudgetInstruction::set_compute_unit_price(
-                    priority_fee_lamports.unwrap_or(5000),
-                ),
-                proposal_accounts_close(
-                    ProposalAccountsCloseAccounts {
-                        multisig,
-                        proposal: proposal_pda.0,
-                        transaction: transaction_pda.0,
-                        rent_collector: rent_collector_key,
-                        system_program: SYS_PROGRAM_ID,
-                    },
-                    None,
-                ),
-            ],
-            &[],
-            blockhash,
-        )
-        .unwrap();
 
-        let transaction = VersionedTransaction::try_new(
-            VersionedMessage::V0(message),
-            &[&*transaction_creator_keypair],
+        let Some(signature) = pipeline
+            .send(vec![instruction], Some(transaction_index))
+            .await?
+        else {
+            return Ok(());
+        };
+
+        output::print(
+            &TransactionOutput {
+                signature,
+                multisig: Some(multisig.to_string()),
+                transaction_index: Some(transaction_index),
+                proposal: Some(proposal_pda.to_string()),
+                transaction: Some(transaction_pda.to_string()),
+            },
+            |result| {
+                println!(
+                    "✅ Collected rent for transaction. Signature: {}",
+                    result.signature.green()
+                )
+            },
         )
-        .expect("Failed to create transaction");
-
-        let signature = send_and_confirm_transaction(&transaction, &rpc_client).await?;
-
-        println!(
-            "✅ Collected rent for transaction. Signature: {}",
-            signature.green()
-        );
-        Ok(())
     }
 }
@@ -3,169 +3,146 @@
 // ─────────────────────────────
 use clap::Args;
 use colored::Colorize;
-use dialoguer::Confirm;
-use indicatif::ProgressBar;
-
-// ─────────────────────────────
-// Standard library
-// ─────────────────────────────
-use std::str::FromStr;
-use std::time::Duration;
-
-// ─────────────────────────────
-// Solana SDK
-// ─────────────────────────────
-use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_compute_budget_interface::ComputeBudgetInstruction;
-use solana_sdk::{
-    message::{v0::Message, VersionedMessage},
-    pubkey::Pubkey,
-    signature::Signer,
-    transaction::VersionedTransaction,
-};
-use solana_system_interface::program::ID as SYS_PROGRAM_ID;
 
 // ─────────────────────────────
 // Fortis SDK
 // ─────────────────────────────
-use fortis_sdk::{
-    client::{get_multisig, proposal_create},
-    pda::{get_proposal_pda, get_transaction_pda, FORTIS_PROGRAM_ID},
-    state::{ProposalCreateAccounts, VaultTransactionMessage},
-};
+use fortis_sdk::state::VaultTransactionMessage;
 
 // ─────────────────────────────
 // Local utilities
 // ─────────────────────────────
-use crate::utils::{create_signer_from_path, send_and_confirm_transaction};
+use crate::address::{parse_address, Address};
+use crate::config::Config;
+use crate::deadline::DeadlineArgs;
+use crate::decode::{decode_vault_message, print_instructions, validate_vault_message};
+use crate::error::Error;
+use crate::info;
+use crate::output::{self, TransactionOutput};
+use crate::pipeline::{TransactionArgs, TransactionPipeline};
+use crate::utils::{format_deadline, get_necessary_alt};
 
 #[derive(Args)]
 pub struct ProposalCreate {
-    /// RPC URL
+    /// RPC URL. Takes precedence over --url, the profile and the Solana CLI config
     #[arg(long)]
     rpc_url: Option<String>,
 
-    /// Path to the Proposal Creator Keypair
+    /// Path to the Proposal Creator Keypair. Defaults to the profile keypair
     #[arg(long)]
-    keypair: String,
+    keypair: Option<String>,
 
-    /// The multisig where the transaction has been proposed
-    #[arg(long)]
-    multisig_pubkey: String,
+    /// The multisig where the transaction has been proposed. Defaults to the profile multisig
+    #[arg(long, value_parser = parse_address)]
+    multisig_pubkey: Option<Address>,
 
-    #[arg(long)]
-    voting_deadline: u64,
+    /// Index of the new proposal, the next one of the multisig. Only with --sign-only, which
+    /// cannot read it from the cluster
+    #[arg(long, requires = "sign_only")]
+    transaction_index: Option<u64>,
+
+    #[command(flatten)]
+    deadline: DeadlineArgs,
     ///transaction message in bs58 format (VaultTransactionMessage->Vec<u8>->bs58_string)
     #[arg(long)]
     transaction_message: String,
 
-    #[arg(long)]
-    priority_fee_lamports: Option<u64>,
+    #[command(flatten)]
+    transaction: TransactionArgs,
 }
 
 impl ProposalCreate {
-    pub async fn execute(self) -> eyre::Result<()> {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
         let Self {
             rpc_url,
             keypair,
             multisig_pubkey,
             transaction_message,
-            voting_deadline,
-            priority_fee_lamports,
+            transaction_index,
+            deadline,
+            transaction,
         } = self;
 
-        let transaction_creator_keypair = create_signer_from_path(keypair).unwrap();
-
-        let transaction_creator = transaction_creator_keypair.pubkey();
-
-        let rpc_url = rpc_url.unwrap_or_else(|| "https://api.mainnet-beta.solana.com".to_string());
-        let rpc_url_clone = rpc_url.clone();
-        let rpc_client = &RpcClient::new(rpc_url);
-
-        let multisig = Pubkey::from_str(&multisig_pubkey).expect("Invalid multisig address");
-
-        let multisig_data = get_multisig(rpc_client, &multisig).await?;
-
-        let transaction_index = multisig_data.transaction_index + 1;
-
-        let transaction_pda = get_transaction_pda(&multisig, transaction_index, None);
-        let proposal_pda = get_proposal_pda(&multisig, transaction_index, None);
-        println!();
-        println!(
-            "{}",
-            "👀 You're about to create a vault transaction, please review the details:".yellow()
+        let transaction_message_vec =
+            bs58::decode(transaction_message)
+                .into_vec()
+                .map_err(|err| {
+                    Error::InvalidInput(format!("Invalid base58 transaction message: {}", err))
+                })?;
+        let transaction_message: VaultTransactionMessage =
+            borsh::from_slice(&transaction_message_vec).map_err(|err| {
+                Error::Deserialization(format!("Invalid transaction message: {}", err))
+            })?;
+        validate_vault_message(&transaction_message)?;
+
+        let pipeline =
+            TransactionPipeline::new(config, "proposal-create", rpc_url, keypair, transaction)?;
+        let transaction_creator = pipeline.member();
+
+        let multisig = config.multisig(multisig_pubkey)?;
+
+        let pipeline = pipeline.multisig(multisig);
+
+        let transaction_index = pipeline.next_transaction_index(transaction_index).await?;
+        let now = pipeline.now().await?;
+        let voting_deadline = deadline.resolve(now)?;
+        // An offline signer has no cluster to load the lookup tables from, their accounts are
+        // shown as missing.
+        let lookup_tables = if pipeline.is_sign_only() {
+            Vec::new()
+        } else {
+            get_necessary_alt(pipeline.rpc_client(), &transaction_message).await?
+        };
+
+        pipeline.print_review(
+            "👀 You're about to create a vault transaction, please review the details:",
+        );
+        info!("Multisig Key:       {}", multisig);
+        info!("Transaction Index:       {}", transaction_index);
+        info!(
+            "Voting Deadline:       {}",
+            format_deadline(voting_deadline, now)
         );
-        println!();
-        println!("RPC Cluster URL:   {}", rpc_url_clone);
-        println!("Program ID:        {}", FORTIS_PROGRAM_ID.to_string());
-        println!("Your Public Key:       {}", transaction_creator);
-        println!();
-        println!("⚙️ Config Parameters");
-        println!("Multisig Key:       {}", multisig_pubkey);
-        println!("Transaction Index:       {}", transaction_index);
-        println!("Voting Deadline:       {}", voting_deadline);
-        println!();
-
-        let proceed = Confirm::new()
-            .with_prompt("Do you want to proceed?")
-            .default(false)
-            .interact()?;
-        if !proceed {
-            println!("OK, aborting.");
+        info!();
+        print_instructions(&decode_vault_message(&transaction_message, &lookup_tables));
+        info!();
+
+        let instructions = |transaction_index| {
+            vec![pipeline.client().proposal_create_instruction(
+                &multisig,
+                transaction_index,
+                &transaction_creator,
+                &transaction_message,
+                voting_deadline,
+            )]
+        };
+
+        let Some((signature, transaction_index)) = pipeline
+            .send_proposal(transaction_index, instructions)
+            .await?
+        else {
             return Ok(());
-        }
-        println!();
-
-        let progress = ProgressBar::new_spinner().with_message("Sending transaction...");
-        progress.enable_steady_tick(Duration::from_millis(100));
-
-        let blockhash = rpc_client
-            .get_latest_blockhash()
-            .await
-            .expect("Failed to get blockhash");
-        let transaction_message_vec = bs58::decode(transaction_message)
-            .into_vec()
-            .expect("erro dedcoding transaction message");
-        let transaction_message: VaultTransactionMessage =
-            borsh::from_slice(&transaction_message_vec).unwrap();
-
-        let message = Message::try_compile(
-            &transaction_creator,
-            &[
-                ComputeBudgetInstruction::set_compute_unit_price(
-                    priority_fee_lamports.unwrap_or(5000),
-                ),
-                proposal_create(
-                    ProposalCreateAccounts {
-                        multisig,
-                        trasaction: transaction_pda.0,
-                        creator: transaction_creator,
-                        proposal: proposal_pda.0,
-                        system_program: SYS_PROGRAM_ID,
-                    },
-                    0,
-                    &transaction_message,
-                    voting_deadline as i64,
-                    None,
-                ),
-            ],
-            &[],
-            blockhash,
-        )
-        .unwrap();
-
-        let transaction = VersionedTransaction::try_new(
-            VersionedMessage::V0(message),
-            &[&*transaction_creator_keypair],
+        };
+
+        let client = pipeline.client();
+        let transaction_pda = client.transaction_address(&multisig, transaction_index);
+        let proposal_pda = client.proposal_address(&multisig, transaction_index);
+
+        output::print(
+            &TransactionOutput {
+                signature,
+                multisig: Some(multisig.to_string()),
+                transaction_index: Some(transaction_index),
+                proposal: Some(proposal_pda.to_string()),
+                transaction: Some(transaction_pda.to_string()),
+            },
+            |result| {
+                println!(
+                    "✅ Transaction #{} created successfully. Signature: {}",
+                    transaction_index,
+                    result.signature.green()
+                )
+            },
         )
-        .expect("Failed to create transaction");
-
-        let signature = send_and_confirm_transaction(&transaction, &rpc_client).await?;
-
-        println!(
-            "✅ Transaction created successfully. Signature: {}",
-            signature.green()
-        );
-        Ok(())
     }
 }
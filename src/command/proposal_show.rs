@@ -0,0 +1,131 @@
+use colored::*;
+use serde::Serialize;
+
+use clap::Args;
+
+use crate::address::{parse_address, Address};
+use crate::client::ProposalState;
+use crate::config::Config;
+use crate::decode::{decode_vault_message, print_instructions, DecodedInstruction};
+use crate::output;
+use crate::utils::{format_deadline, format_timestamp, proposal_status};
+
+#[derive(Args)]
+pub struct ProposalShow {
+    /// RPC URL. Takes precedence over --url, the profile and the Solana CLI config
+    #[arg(long)]
+    rpc_url: Option<String>,
+
+    /// The multisig where the transaction has been proposed. Defaults to the profile multisig
+    #[arg(long, value_parser = parse_address)]
+    multisig_pubkey: Option<Address>,
+
+    /// Index of the transaction to show
+    #[arg(long)]
+    transaction_index: u64,
+}
+
+#[derive(Serialize)]
+struct ProposalShowOutput {
+    multisig: String,
+    transaction_index: u64,
+    proposal: String,
+    transaction: String,
+    creator: String,
+    vault: String,
+    /// `Active`, `Expired`, `Approved` or `Executed`
+    status: &'static str,
+    created_at: i64,
+    deadline: i64,
+    threshold: u16,
+    approved: Vec<String>,
+    instructions: Vec<DecodedInstruction>,
+}
+
+impl ProposalShow {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
+        let Self {
+            rpc_url,
+            multisig_pubkey,
+            transaction_index,
+        } = self;
+
+        let client = config.client(config.rpc_url(rpc_url)?);
+
+        let multisig = config.multisig(multisig_pubkey)?;
+
+        let ProposalState {
+            multisig: multisig_data,
+            proposal,
+            transaction,
+            lookup_tables,
+        } = client.proposal_state(&multisig, transaction_index).await?;
+        let instructions = decode_vault_message(&transaction.message, &lookup_tables);
+        let now = client.cluster_time().await?;
+
+        output::print(
+            &ProposalShowOutput {
+                multisig: multisig.to_string(),
+                transaction_index,
+                proposal: client
+                    .proposal_address(&multisig, transaction_index)
+                    .to_string(),
+                transaction: client
+                    .transaction_address(&multisig, transaction_index)
+                    .to_string(),
+                creator: transaction.creator.to_string(),
+                vault: client.vault(&multisig).to_string(),
+                status: proposal_status(&proposal, now),
+                created_at: proposal.timestamp,
+                deadline: proposal.deadline,
+                threshold: multisig_data.threshold,
+                approved: proposal.approved.iter().map(|pk| pk.to_string()).collect(),
+                instructions,
+            },
+            |proposal| print_proposal(proposal, now),
+        )
+    }
+}
+
+fn print_proposal(proposal: &ProposalShowOutput, now: i64) {
+    println!(
+        "\n{} Proposal #{}",
+        "▶".cyan(),
+        proposal.transaction_index.to_string().bold()
+    );
+    println!("  Multisig    : {}", proposal.multisig);
+    println!("  Address     : {}", proposal.proposal.dimmed());
+    println!("  Transaction : {}", proposal.transaction.dimmed());
+    println!("  Creator     : {}", proposal.creator);
+    println!("  Vault       : {}", proposal.vault);
+    println!("  Created At  : {}", format_timestamp(proposal.created_at));
+    println!(
+        "  Deadline    : {}",
+        format_deadline(proposal.deadline, now)
+    );
+    println!(
+        "  Approvals   : {}/{}",
+        proposal.approved.len().to_string().green(),
+        proposal.threshold
+    );
+
+    if proposal.approved.is_empty() {
+        println!("  Approvers   : {}", "None".dimmed());
+    } else {
+        for pk in &proposal.approved {
+            println!("    • {}", pk.bright_white());
+        }
+    }
+
+    let status = match proposal.status {
+        "Expired" => proposal.status.red(),
+        "Active" => proposal.status.yellow(),
+        "Approved" => proposal.status.green(),
+        _ => proposal.status.bright_green(),
+    };
+
+    println!("  Status      : {}\n", status.bold());
+
+    print_instructions(&proposal.instructions);
+    println!();
+}
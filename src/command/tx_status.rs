@@ -0,0 +1,231 @@
+use std::str::FromStr;
+
+use clap::Args;
+use colored::*;
+use serde::Serialize;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::{
+    TransactionConfirmationStatus, UiTransactionEncoding,
+};
+
+use crate::config::Config;
+use crate::decode::find_fortis_instructions;
+use crate::journal::{self, Journal, JournalEntry, JournalStatus};
+use crate::output;
+use crate::program_error;
+use crate::utils::{format_timestamp, is_expired};
+
+#[derive(Args)]
+pub struct TxStatus {
+    /// RPC URL. Defaults to the cluster the transaction was sent to, when it is in the journal
+    #[arg(long)]
+    rpc_url: Option<String>,
+
+    /// Signature of the transaction
+    signature: Signature,
+}
+
+#[derive(Serialize)]
+pub struct TxStatusOutput {
+    pub signature: String,
+    /// `pending`, `expired`, `failed`, `processed`, `confirmed`, `finalized`, or `unknown` for a
+    /// transaction the cluster does not know and that is not in the journal, or whose status
+    /// could not be checked
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// What `error` means, when the failing program is Fortis, System, SPL Token or the loader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+    /// Subcommand that sent the transaction, from the journal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sent_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_index: Option<u64>,
+    /// Fortis instructions of the transaction, once it landed
+    pub instructions: Vec<&'static str>,
+}
+
+impl TxStatusOutput {
+    /// An `unknown` status, before anything is asked of the cluster.
+    pub fn unknown(signature: String, entry: Option<&JournalEntry>) -> Self {
+        Self {
+            signature,
+            status: "unknown",
+            slot: None,
+            error: None,
+            explanation: None,
+            command: entry.map(|entry| entry.command.clone()),
+            sent_at: entry.map(|entry| entry.sent_at),
+            multisig: entry.and_then(|entry| entry.multisig.clone()),
+            transaction_index: entry.and_then(|entry| entry.transaction_index),
+            instructions: Vec::new(),
+        }
+    }
+}
+
+impl TxStatus {
+    pub async fn execute(self, config: &Config) -> eyre::Result<()> {
+        let Self { rpc_url, signature } = self;
+
+        let entry = journal::load(&Journal::path(config))?
+            .into_iter()
+            .find(|entry| entry.signature == signature.to_string());
+
+        let rpc_url = config
+            .rpc_url(rpc_url.or_else(|| entry.as_ref().map(|entry| entry.rpc_url.clone())))?;
+        let rpc_client = config.rpc_client(rpc_url);
+
+        let status = check_status(config, &rpc_client, &signature, entry.as_ref()).await?;
+
+        output::print(&status, print_status)
+    }
+}
+
+/// Looks up the outcome of `signature`, using its journal `entry` to tell an expired transaction
+/// from one still in flight, and records it in the journal once it is final.
+pub async fn check_status(
+    config: &Config,
+    rpc_client: &RpcClient,
+    signature: &Signature,
+    entry: Option<&JournalEntry>,
+) -> eyre::Result<TxStatusOutput> {
+    let mut output = TxStatusOutput::unknown(signature.to_string(), entry);
+
+    let status = rpc_client
+        .get_signature_statuses_with_history(&[*signature])
+        .await?
+        .value
+        .pop()
+        .flatten();
+
+    match status {
+        Some(status) => {
+            let confirmation_status = status.confirmation_status();
+            output.slot = Some(status.slot);
+            output.status = match (&status.err, &confirmation_status) {
+                (Some(_), _) => "failed",
+                (None, TransactionConfirmationStatus::Processed) => "processed",
+                (None, TransactionConfirmationStatus::Confirmed) => "confirmed",
+                (None, TransactionConfirmationStatus::Finalized) => "finalized",
+            };
+            output.error = status.err.as_ref().map(|err| err.to_string());
+
+            // Transactions can only be fetched once confirmed.
+            if confirmation_status != TransactionConfirmationStatus::Processed {
+                let transaction = rpc_client
+                    .get_transaction_with_config(
+                        signature,
+                        RpcTransactionConfig {
+                            encoding: Some(UiTransactionEncoding::Base64),
+                            commitment: Some(CommitmentConfig::confirmed()),
+                            max_supported_transaction_version: Some(0),
+                        },
+                    )
+                    .await
+                    .ok()
+                    .map(|transaction| transaction.transaction);
+                let logs: Vec<String> = transaction
+                    .as_ref()
+                    .and_then(|transaction| transaction.meta.clone())
+                    .and_then(|meta| meta.log_messages.into())
+                    .unwrap_or_default();
+                if let Some(transaction) =
+                    transaction.and_then(|transaction| transaction.transaction.decode())
+                {
+                    if let Some(err) = &status.err {
+                        output.explanation = program_error::explain(
+                            err,
+                            &transaction.message,
+                            &logs,
+                            &config.program_id(),
+                        );
+                    }
+                    let instructions =
+                        find_fortis_instructions(&transaction.message, &config.program_id());
+                    if output.multisig.is_none() {
+                        output.multisig = instructions
+                            .iter()
+                            .find_map(|ix| ix.multisig)
+                            .map(|multisig| multisig.to_string());
+                    }
+                    output.instructions = instructions.iter().map(|ix| ix.name).collect();
+                }
+            }
+        }
+        None => {
+            if let Some(entry) = entry {
+                let blockhash = Hash::from_str(&entry.blockhash)?;
+                let nonce = entry.nonce.as_deref().map(Pubkey::from_str).transpose()?;
+                output.status = if is_expired(rpc_client, &blockhash, nonce).await? {
+                    "expired"
+                } else {
+                    "pending"
+                };
+            }
+        }
+    }
+
+    let final_status = match output.status {
+        "confirmed" | "finalized" => Some(JournalStatus::Confirmed),
+        "failed" => Some(JournalStatus::Failed),
+        "expired" => Some(JournalStatus::Expired),
+        _ => None,
+    };
+    if let Some(final_status) = final_status {
+        journal::set_status(&Journal::path(config), &output.signature, final_status)?;
+    }
+
+    Ok(output)
+}
+
+pub fn print_status(status: &TxStatusOutput) {
+    println!("\n{} {}", "▶".cyan(), status.signature.bold());
+
+    let label = match status.status {
+        "failed" | "expired" => status.status.red(),
+        "pending" | "processed" => status.status.yellow(),
+        "unknown" => status.status.dimmed(),
+        _ => status.status.green(),
+    };
+    match status.slot {
+        Some(slot) => println!("  Status       : {} (slot {})", label.bold(), slot),
+        None => println!("  Status       : {}", label.bold()),
+    }
+    if let Some(error) = &status.error {
+        println!("  Error        : {}", error.red());
+    }
+    if let Some(explanation) = &status.explanation {
+        println!("  Explanation  : {}", explanation.yellow());
+    }
+    if let Some(command) = &status.command {
+        println!("  Command      : {}", command);
+    }
+    if let Some(sent_at) = status.sent_at {
+        println!("  Sent At      : {}", format_timestamp(sent_at));
+    }
+    if let Some(multisig) = &status.multisig {
+        println!("  Multisig     : {}", multisig);
+    }
+    if let Some(transaction_index) = status.transaction_index {
+        println!("  Index        : {}", transaction_index);
+    }
+    if !status.instructions.is_empty() {
+        println!("  Instructions : {}", status.instructions.join(", "));
+    }
+    if status.status == "unknown" && status.error.is_none() {
+        println!(
+            "  {}",
+            "Not found: never sent, expired, or older than the node's history".dimmed()
+        );
+    }
+    println!();
+}
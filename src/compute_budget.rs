@@ -0,0 +1,280 @@
+use std::{fmt, str::FromStr};
+
+use clap::Args;
+use colored::Colorize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_instruction::Instruction;
+use solana_message::AddressLookupTableAccount;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::info;
+use crate::utils::{
+    estimate_compute_unit_limit, DEFAULT_COMPUTE_UNIT_LIMIT, MAX_COMPUTE_UNIT_LIMIT,
+};
+
+/// Base fee the runtime charges for every signature, in lamports.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+/// Percentile of recent fees used by `--priority-fee auto`.
+pub const AUTO_PERCENTILE: u8 = 75;
+
+/// Compute unit price used when recent fees cannot be queried, as with `--sign-only`.
+pub const DEFAULT_PRIORITY_FEE: u64 = 5000;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// How the compute unit price is chosen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriorityFee {
+    /// The [`AUTO_PERCENTILE`] of recent fees paid to write the same accounts
+    Auto,
+    /// A percentile of recent fees paid to write the same accounts, written `p90`
+    Percentile(u8),
+    /// A fixed price in micro-lamports per compute unit
+    MicroLamports(u64),
+}
+
+impl FromStr for PriorityFee {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "auto" {
+            return Ok(PriorityFee::Auto);
+        }
+        if let Some(percentile) = value.strip_prefix('p') {
+            return match percentile.parse() {
+                Ok(percentile @ 0..=100) => Ok(PriorityFee::Percentile(percentile)),
+                _ => Err(format!("invalid percentile `{value}`, expected p0 to p100")),
+            };
+        }
+        value.parse().map(PriorityFee::MicroLamports).map_err(|_| {
+            format!("invalid priority fee `{value}`, expected `auto`, a percentile such as `p90` or micro-lamports per compute unit")
+        })
+    }
+}
+
+impl fmt::Display for PriorityFee {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PriorityFee::Auto => write!(f, "auto"),
+            PriorityFee::Percentile(percentile) => write!(f, "p{percentile}"),
+            PriorityFee::MicroLamports(price) => write!(f, "{price}"),
+        }
+    }
+}
+
+// Profiles written before `auto` existed store a plain number.
+impl Serialize for PriorityFee {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PriorityFee::MicroLamports(price) => serializer.serialize_u64(*price),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PriorityFee {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            MicroLamports(u64),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::MicroLamports(price) => Ok(PriorityFee::MicroLamports(price)),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ComputeBudgetArgs {
+    /// Compute unit price: `auto`, a percentile of recent fees paid to write the same accounts
+    /// such as `p90`, or micro-lamports per compute unit. Defaults to the profile value, or auto
+    #[arg(long, alias = "priority-fee-lamports")]
+    pub priority_fee: Option<PriorityFee>,
+
+    /// Upper bound on the total transaction fee in lamports. The compute unit price is lowered
+    /// to stay under it
+    #[arg(long)]
+    pub max_fee_lamports: Option<u64>,
+
+    /// Compute unit limit. Estimated by simulating the transaction when omitted
+    #[arg(long)]
+    pub compute_unit_limit: Option<u32>,
+
+    /// Percentage added to the simulated compute units. Defaults to the profile value, or 20
+    #[arg(long)]
+    pub compute_unit_margin: Option<u32>,
+}
+
+/// The compute unit limit and price of a transaction, and the fee they add up to.
+pub struct ComputeBudget {
+    pub priority_fee: PriorityFee,
+    pub unit_limit: u32,
+    /// Micro-lamports per compute unit
+    pub unit_price: u64,
+    /// Total fee in lamports, signatures included
+    pub fee: u64,
+}
+
+impl ComputeBudgetArgs {
+    /// Prices and sizes the transaction built by `instructions` for a given compute unit limit
+    /// and price. `offline` skips the RPC calls, falling back to [`DEFAULT_PRIORITY_FEE`] and
+    /// [`DEFAULT_COMPUTE_UNIT_LIMIT`] unless they were given explicitly.
+    pub async fn resolve(
+        &self,
+        config: &Config,
+        rpc_client: &RpcClient,
+        payer: &Pubkey,
+        lookup_tables: &[AddressLookupTableAccount],
+        offline: bool,
+        instructions: impl Fn(u32, u64) -> Vec<Instruction>,
+    ) -> eyre::Result<ComputeBudget> {
+        let priority_fee = match config.priority_fee(self.priority_fee) {
+            PriorityFee::Auto | PriorityFee::Percentile(_) if offline => {
+                PriorityFee::MicroLamports(DEFAULT_PRIORITY_FEE)
+            }
+            priority_fee => priority_fee,
+        };
+        let unit_price = match priority_fee {
+            PriorityFee::MicroLamports(price) => price,
+            PriorityFee::Auto => {
+                recent_priority_fee(rpc_client, &instructions(0, 0), AUTO_PERCENTILE).await?
+            }
+            PriorityFee::Percentile(percentile) => {
+                recent_priority_fee(rpc_client, &instructions(0, 0), percentile).await?
+            }
+        };
+
+        let unit_limit = match self.compute_unit_limit {
+            Some(limit) => limit,
+            // Offline there is no cluster to simulate against.
+            None if offline => DEFAULT_COMPUTE_UNIT_LIMIT,
+            None => {
+                estimate_compute_unit_limit(
+                    rpc_client,
+                    payer,
+                    &instructions(MAX_COMPUTE_UNIT_LIMIT, unit_price),
+                    lookup_tables,
+                    config.compute_unit_margin(self.compute_unit_margin),
+                    &config.program_id(),
+                )
+                .await?
+            }
+        };
+
+        let signatures = count_signatures(payer, &instructions(unit_limit, unit_price));
+        let base_fee = LAMPORTS_PER_SIGNATURE * signatures;
+        let unit_price = match self.max_fee_lamports {
+            Some(max_fee) if base_fee > max_fee => {
+                return Err(Error::InvalidInput(format!(
+                    "--max-fee-lamports {} is below the base fee of {} lamports",
+                    max_fee, base_fee
+                ))
+                .into());
+            }
+            Some(max_fee) => unit_price.min(max_unit_price(max_fee - base_fee, unit_limit)),
+            None => unit_price,
+        };
+
+        Ok(ComputeBudget {
+            priority_fee,
+            unit_limit,
+            unit_price,
+            fee: base_fee + priority_fee_lamports(unit_limit, unit_price),
+        })
+    }
+}
+
+impl ComputeBudget {
+    /// Prints the budget as part of a review screen.
+    pub fn print(&self) {
+        info!("⛽ Fees");
+        info!("Compute Unit Limit:       {}", self.unit_limit);
+        match self.priority_fee {
+            PriorityFee::MicroLamports(_) => {
+                info!("Priority Fee:       {} micro-lamports/CU", self.unit_price)
+            }
+            PriorityFee::Auto => info!(
+                "Priority Fee:       {} micro-lamports/CU (p{} of recent fees)",
+                self.unit_price, AUTO_PERCENTILE
+            ),
+            priority_fee => info!(
+                "Priority Fee:       {} micro-lamports/CU ({} of recent fees)",
+                self.unit_price, priority_fee
+            ),
+        }
+        info!(
+            "Estimated Fee:       {}",
+            format!(
+                "{} SOL ({} lamports)",
+                self.fee as f64 / LAMPORTS_PER_SOL as f64,
+                self.fee
+            )
+            .bold()
+        );
+        info!();
+    }
+}
+
+/// The `percentile` of the fees paid over the last slots by transactions writing any of the
+/// writable accounts of `instructions`.
+async fn recent_priority_fee(
+    rpc_client: &RpcClient,
+    instructions: &[Instruction],
+    percentile: u8,
+) -> eyre::Result<u64> {
+    let mut accounts: Vec<Pubkey> = Vec::new();
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_writable && !accounts.contains(&meta.pubkey) {
+            accounts.push(meta.pubkey);
+        }
+    }
+
+    let mut fees: Vec<u64> = rpc_client
+        .get_recent_prioritization_fees(&accounts)
+        .await
+        .map_err(|err| Error::Rpc(format!("Failed to fetch recent priority fees: {}", err)))?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+    fees.sort_unstable();
+
+    if fees.is_empty() {
+        return Ok(0);
+    }
+    let rank = (fees.len() * usize::from(percentile)).div_ceil(100);
+    Ok(fees[rank.saturating_sub(1)])
+}
+
+/// Number of signatures the transaction needs: the payer and every distinct signer account.
+fn count_signatures(payer: &Pubkey, instructions: &[Instruction]) -> u64 {
+    let mut signers = vec![*payer];
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_signer && !signers.contains(&meta.pubkey) {
+            signers.push(meta.pubkey);
+        }
+    }
+    signers.len() as u64
+}
+
+/// Priority fee in lamports, rounded up as the runtime does.
+fn priority_fee_lamports(unit_limit: u32, unit_price: u64) -> u64 {
+    (u128::from(unit_limit) * u128::from(unit_price)).div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64
+}
+
+/// Highest compute unit price keeping the priority fee of `unit_limit` units within `budget`
+/// lamports.
+fn max_unit_price(budget: u64, unit_limit: u32) -> u64 {
+    if unit_limit == 0 {
+        return u64::MAX;
+    }
+    (u128::from(budget) * MICRO_LAMPORTS_PER_LAMPORT / u128::from(unit_limit))
+        .min(u128::from(u64::MAX)) as u64
+}
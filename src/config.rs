@@ -0,0 +1,487 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use clap::Args;
+use eyre::{eyre, WrapErr};
+use fortis_sdk::pda::FORTIS_PROGRAM_ID;
+use serde::{Deserialize, Serialize};
+use solana_clap_v3_utils::input_validators::normalize_to_url_if_moniker;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::address::{self, Address};
+use crate::client::FortisClient;
+use crate::compute_budget::PriorityFee;
+use crate::error::Error;
+use crate::output::OutputFormat;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Builds the RPC client of a command from the resolved RPC URL and commitment.
+pub type RpcClientFactory = Box<dyn Fn(String, CommitmentConfig) -> RpcClient + Send + Sync>;
+
+/// Percentage added to the simulated compute units when sizing the compute unit limit.
+pub const DEFAULT_COMPUTE_UNIT_MARGIN: u32 = 20;
+
+/// Keys that can be read and written with `config get` / `config set`.
+pub const PROFILE_KEYS: &[&str] = &[
+    "rpc_url",
+    "commitment",
+    "keypair",
+    "multisig",
+    "priority_fee",
+    "compute_unit_margin",
+    "program_id",
+];
+
+// Options accepted by every subcommand.
+#[derive(Args)]
+pub struct GlobalArgs {
+    /// Profile from the config file to use (defaults to `default_profile` or "default")
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Path to the config file. Defaults to ~/.config/fortis/config.toml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Path to the Solana CLI config read as a fallback. Defaults to
+    /// ~/.config/solana/cli/config.yml
+    #[arg(long, global = true)]
+    pub solana_config: Option<PathBuf>,
+
+    /// RPC URL or moniker: mainnet-beta, testnet, devnet, localhost (or m, t, d, l)
+    #[arg(short = 'u', long = "url", global = true)]
+    pub url: Option<String>,
+
+    /// Commitment to read accounts at and to wait for after sending. Defaults to the profile, the
+    /// Solana CLI config, or confirmed
+    #[arg(long, global = true, value_parser = ["processed", "confirmed", "finalized"])]
+    pub commitment: Option<String>,
+
+    /// Fortis program ID, for non-canonical deployments. Defaults to the mainnet program
+    #[arg(long, global = true, value_parser = address::parse_address)]
+    pub program_id: Option<Address>,
+
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Skip the confirmation prompt. Required when stdin is not a terminal
+    #[arg(long, visible_alias = "no-confirm", global = true, env = "FORTIS_YES")]
+    pub yes: bool,
+}
+
+/// On-disk layout of `~/.config/fortis/config.toml`.
+#[derive(Default, Serialize, Deserialize)]
+pub struct ConfigFile {
+    /// Profile used when `--profile` is not passed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+
+    /// Labels that pubkey arguments accept in place of an address, shared by all profiles.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub address_book: BTreeMap<String, String>,
+}
+
+/// A named set of defaults, e.g. `devnet` or `mainnet`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_url: Option<String>,
+
+    /// `processed`, `confirmed` or `finalized`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<String>,
+
+    /// Default keypair path (or `usb://ledger` URL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keypair: Option<String>,
+
+    /// Default multisig address or address book label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<String>,
+
+    /// Default compute unit price: `auto`, a percentile such as `p90`, or micro-lamports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_fee: Option<PriorityFee>,
+
+    /// Percentage added to the simulated compute units when sizing the compute unit limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compute_unit_margin: Option<u32>,
+
+    /// Fortis program ID of a forked deployment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub program_id: Option<String>,
+}
+
+impl ConfigFile {
+    pub fn default_path() -> eyre::Result<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| eyre!("Could not determine home directory"))?;
+        Ok(home.join(".config").join("fortis").join("config.toml"))
+    }
+
+    /// Reads the config file, returning an empty config when it does not exist yet.
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&contents)
+            .wrap_err_with(|| format!("Failed to parse config file {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .wrap_err_with(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let contents = toml::to_string_pretty(self)?;
+        fs::write(path, contents)
+            .wrap_err_with(|| format!("Failed to write config file {}", path.display()))
+    }
+
+    pub fn active_profile_name(&self, requested: Option<&str>) -> String {
+        requested
+            .map(str::to_string)
+            .or_else(|| self.default_profile.clone())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+}
+
+impl Profile {
+    pub fn get(&self, key: &str) -> eyre::Result<Option<String>> {
+        Ok(match key {
+            "rpc_url" => self.rpc_url.clone(),
+            "commitment" => self.commitment.clone(),
+            "keypair" => self.keypair.clone(),
+            "multisig" => self.multisig.clone(),
+            "priority_fee" => self.priority_fee.map(|fee| fee.to_string()),
+            "compute_unit_margin" => self.compute_unit_margin.map(|margin| margin.to_string()),
+            "program_id" => self.program_id.clone(),
+            _ => return Err(unknown_key(key)),
+        })
+    }
+
+    /// Sets `key` to `value`, validating the value first.
+    pub fn set(&mut self, key: &str, value: String) -> eyre::Result<()> {
+        match key {
+            "rpc_url" => self.rpc_url = Some(value),
+            "commitment" => {
+                parse_commitment(&value)?;
+                self.commitment = Some(value);
+            }
+            "keypair" => self.keypair = Some(value),
+            "multisig" => {
+                address::parse_address(&value).map_err(|err| {
+                    Error::InvalidInput(format!("Invalid multisig {value}: {err}"))
+                })?;
+                self.multisig = Some(value);
+            }
+            "priority_fee" => {
+                let fee = value
+                    .parse()
+                    .map_err(|err| Error::InvalidInput(format!("Invalid priority fee: {err}")))?;
+                self.priority_fee = Some(fee);
+            }
+            "compute_unit_margin" => {
+                let margin = value.parse().map_err(|_| {
+                    Error::InvalidInput(format!("Invalid compute unit margin: {value}"))
+                })?;
+                self.compute_unit_margin = Some(margin);
+            }
+            "program_id" => {
+                address::parse_address(&value).map_err(|err| {
+                    Error::InvalidInput(format!("Invalid program ID {value}: {err}"))
+                })?;
+                self.program_id = Some(value);
+            }
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+/// The subset of the Solana CLI `config.yml` used as a last-resort fallback.
+#[derive(Default, Deserialize)]
+pub struct SolanaCliConfig {
+    #[serde(default)]
+    pub json_rpc_url: Option<String>,
+    #[serde(default)]
+    pub keypair_path: Option<String>,
+    #[serde(default)]
+    pub commitment: Option<String>,
+}
+
+impl SolanaCliConfig {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| {
+            home.join(".config")
+                .join("solana")
+                .join("cli")
+                .join("config.yml")
+        })
+    }
+
+    /// Reads the Solana CLI config, returning an empty config when it does not exist.
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read Solana CLI config {}", path.display()))?;
+        serde_yaml::from_str(&contents)
+            .wrap_err_with(|| format!("Failed to parse Solana CLI config {}", path.display()))
+    }
+
+    /// The commitment of the Solana CLI config, including the legacy names `solana config set`
+    /// still accepts. An unknown value is ignored with a warning, as the config is only a
+    /// fallback.
+    pub fn commitment(&self) -> Option<CommitmentConfig> {
+        let commitment = self.commitment.as_deref()?;
+        let parsed = match commitment {
+            "recent" => Some(CommitmentConfig::processed()),
+            "single" | "singleGossip" => Some(CommitmentConfig::confirmed()),
+            "root" | "max" => Some(CommitmentConfig::finalized()),
+            _ => CommitmentConfig::from_str(commitment).ok(),
+        };
+        if parsed.is_none() {
+            eprintln!("⚠ Ignoring commitment '{commitment}' of the Solana CLI config, it is not a commitment level");
+        }
+        parsed
+    }
+}
+
+/// Settings resolved from the config file and the selected profile, shared by all commands.
+///
+/// Every value is looked up in order: command argument, global flag, Fortis profile,
+/// Solana CLI config, built-in default.
+pub struct Config {
+    pub path: PathBuf,
+    pub profile_name: String,
+    pub profile: Profile,
+    pub solana_cli: SolanaCliConfig,
+    /// Proceed without asking for confirmation.
+    pub yes: bool,
+    url: Option<String>,
+    commitment: CommitmentConfig,
+    program_id: Pubkey,
+    address_book: BTreeMap<String, String>,
+    rpc_client: Option<RpcClientFactory>,
+}
+
+impl Config {
+    /// Resolves the selected profile. Unless `allow_missing_profile` is set, a profile that was
+    /// asked for by name but is not in the file is an error.
+    pub fn load(args: GlobalArgs, allow_missing_profile: bool) -> eyre::Result<Self> {
+        let GlobalArgs {
+            profile,
+            config: path,
+            solana_config,
+            url,
+            commitment,
+            program_id,
+            yes,
+            ..
+        } = args;
+        let path = match path {
+            Some(path) => path,
+            None => ConfigFile::default_path()?,
+        };
+        let file = ConfigFile::load(&path)?;
+        let profile_name = file.active_profile_name(profile.as_deref());
+
+        let profile = match file.profiles.get(&profile_name) {
+            Some(profile) => profile.clone(),
+            // An explicitly requested profile must exist, silently falling back is how
+            // transactions end up on the wrong cluster.
+            None if !allow_missing_profile
+                && (profile.is_some() || file.default_profile.is_some()) =>
+            {
+                return Err(Error::InvalidInput(format!(
+                    "Profile '{}' not found in {}",
+                    profile_name,
+                    path.display()
+                ))
+                .into())
+            }
+            None => Profile::default(),
+        };
+
+        // Only a fallback: a config the Solana CLI itself would choke on must not stop us.
+        let solana_cli = match solana_config.or_else(SolanaCliConfig::default_path) {
+            Some(path) => SolanaCliConfig::load(&path).unwrap_or_else(|err| {
+                eprintln!("⚠ Ignoring the Solana CLI config: {err:#}");
+                SolanaCliConfig::default()
+            }),
+            None => SolanaCliConfig::default(),
+        };
+
+        let commitment = match commitment.as_ref().or(profile.commitment.as_ref()) {
+            Some(commitment) => parse_commitment(commitment)?,
+            None => solana_cli
+                .commitment()
+                .unwrap_or_else(CommitmentConfig::confirmed),
+        };
+
+        let address_book = file.address_book;
+        let program_id = match (program_id, &profile.program_id) {
+            (Some(program_id), _) => lookup(&address_book, &program_id)?,
+            (None, Some(program_id)) => {
+                let program_id = address::parse_address(program_id).map_err(|err| {
+                    Error::InvalidInput(format!(
+                        "Invalid program ID in profile {program_id}: {err}"
+                    ))
+                })?;
+                lookup(&address_book, &program_id)?
+            }
+            (None, None) => FORTIS_PROGRAM_ID,
+        };
+
+        Ok(Self {
+            path,
+            profile_name,
+            profile,
+            solana_cli,
+            yes,
+            url,
+            commitment,
+            program_id,
+            address_book,
+            rpc_client: None,
+        })
+    }
+
+    /// Builds the RPC clients of every command with `rpc_client` instead of connecting over HTTP,
+    /// e.g. to serve recorded accounts through `RpcClient::new_sender` in tests.
+    pub fn with_rpc_client(
+        mut self,
+        rpc_client: impl Fn(String, CommitmentConfig) -> RpcClient + Send + Sync + 'static,
+    ) -> Self {
+        self.rpc_client = Some(Box::new(rpc_client));
+        self
+    }
+
+    /// `--rpc-url`, then `--url`, the profile, then the Solana CLI config. There is no default:
+    /// guessing mainnet is how a transaction meant for devnet ends up on mainnet.
+    /// Cluster monikers such as `devnet` are expanded to their URL.
+    pub fn rpc_url(&self, rpc_url: Option<String>) -> eyre::Result<String> {
+        let rpc_url = rpc_url
+            .or_else(|| self.url.clone())
+            .or_else(|| self.profile.rpc_url.clone())
+            .or_else(|| self.solana_cli.json_rpc_url.clone())
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "No RPC URL given: pass -u <URL or moniker> such as `-u devnet`, or run \
+                     `config set rpc_url <URL> --profile {}`",
+                    self.profile_name
+                ))
+            })?;
+        Ok(normalize_to_url_if_moniker(rpc_url))
+    }
+
+    /// `--program-id`, then the profile, then the canonical Fortis program.
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    pub fn rpc_client(&self, rpc_url: String) -> RpcClient {
+        match &self.rpc_client {
+            Some(rpc_client) => rpc_client(rpc_url, self.commitment),
+            None => RpcClient::new_with_commitment(rpc_url, self.commitment),
+        }
+    }
+
+    pub fn client(&self, rpc_url: String) -> FortisClient {
+        FortisClient::new(self.rpc_client(rpc_url), self.program_id())
+    }
+
+    pub fn keypair(&self, keypair: Option<String>) -> eyre::Result<String> {
+        keypair
+            .or_else(|| self.profile.keypair.clone())
+            .or_else(|| self.solana_cli.keypair_path.clone())
+            .ok_or_else(|| self.missing("keypair"))
+    }
+
+    /// `--multisig-pubkey`, else the profile multisig. Either may be an address book label.
+    pub fn multisig(&self, multisig: Option<Address>) -> eyre::Result<Pubkey> {
+        let multisig = match (multisig, &self.profile.multisig) {
+            (Some(multisig), _) => multisig,
+            (None, Some(multisig)) => address::parse_address(multisig).map_err(|err| {
+                Error::InvalidInput(format!("Invalid multisig in profile {multisig}: {err}"))
+            })?,
+            (None, None) => return Err(self.missing("multisig")),
+        };
+        self.pubkey(&multisig)
+    }
+
+    /// The pubkey of `address`, looking labels up in the address book.
+    pub fn pubkey(&self, address: &Address) -> eyre::Result<Pubkey> {
+        lookup(&self.address_book, address)
+    }
+
+    /// Priority fee mode: the flag, else the profile, else `auto`.
+    pub fn priority_fee(&self, priority_fee: Option<PriorityFee>) -> PriorityFee {
+        priority_fee
+            .or(self.profile.priority_fee)
+            .unwrap_or(PriorityFee::Auto)
+    }
+
+    /// Compute unit margin in percent: the flag, else the profile, else 20%.
+    pub fn compute_unit_margin(&self, compute_unit_margin: Option<u32>) -> u32 {
+        compute_unit_margin
+            .or(self.profile.compute_unit_margin)
+            .unwrap_or(DEFAULT_COMPUTE_UNIT_MARGIN)
+    }
+
+    fn missing(&self, key: &str) -> eyre::Report {
+        Error::InvalidInput(format!(
+            "No {key} given: pass it as an argument or run `config set {key} <VALUE> --profile {}`",
+            self.profile_name
+        ))
+        .into()
+    }
+}
+
+pub fn parse_commitment(commitment: &str) -> eyre::Result<CommitmentConfig> {
+    CommitmentConfig::from_str(commitment).map_err(|_| {
+        Error::InvalidInput(format!(
+            "Invalid commitment '{commitment}', expected processed, confirmed or finalized"
+        ))
+        .into()
+    })
+}
+
+fn lookup(address_book: &BTreeMap<String, String>, address: &Address) -> eyre::Result<Pubkey> {
+    let label = match address {
+        Address::Pubkey(pubkey) => return Ok(*pubkey),
+        Address::Label(label) => label,
+    };
+    let value = address_book.get(label).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "Unknown address book label '{label}': add it with \
+             `config address-book set {label} <ADDRESS>`"
+        ))
+    })?;
+    address::parse_pubkey(value).map_err(|err| {
+        Error::InvalidInput(format!(
+            "Invalid address {value} for label '{label}' in the address book: {err}"
+        ))
+        .into()
+    })
+}
+
+fn unknown_key(key: &str) -> eyre::Report {
+    Error::InvalidInput(format!(
+        "Unknown config key '{key}', expected one of: {}",
+        PROFILE_KEYS.join(", ")
+    ))
+    .into()
+}
@@ -0,0 +1,138 @@
+use std::str::FromStr;
+
+use chrono::DateTime;
+use clap::{Args, ValueEnum};
+
+use crate::error::Error;
+use crate::utils::format_timestamp;
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+
+/// When voting on a new proposal ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deadline {
+    /// Unix seconds
+    At(i64),
+    /// Seconds from now, on the cluster clock
+    In(i64),
+}
+
+impl FromStr for Deadline {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        // Unix seconds, as every version before durations were accepted.
+        if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+            return value
+                .parse()
+                .map(Deadline::At)
+                .map_err(|_| format!("invalid deadline `{value}`, too far in the future"));
+        }
+        if let Some(seconds) = parse_duration(value) {
+            return Ok(Deadline::In(seconds));
+        }
+        DateTime::parse_from_rfc3339(value)
+            .map(|time| Deadline::At(time.timestamp()))
+            .map_err(|_| {
+                format!("invalid deadline `{value}`, expected a duration such as `3d` or `36h`, an RFC 3339 time such as `2025-11-01T12:00:00Z`, or unix seconds")
+            })
+    }
+}
+
+/// Durations such as `3d`, `36h` or `1d12h`: numbers followed by `w`, `d`, `h`, `m` or `s`.
+fn parse_duration(value: &str) -> Option<i64> {
+    let mut seconds: i64 = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'w' => WEEK,
+            'd' => DAY,
+            'h' => HOUR,
+            'm' => MINUTE,
+            's' => 1,
+            _ => return None,
+        };
+        let count: i64 = number.parse().ok()?;
+        seconds = seconds.checked_add(count.checked_mul(unit)?)?;
+        number.clear();
+    }
+    // A trailing number without its unit is ambiguous.
+    number.is_empty().then_some(seconds)
+}
+
+/// Voting periods for `--voting-period`, counted from now.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum VotingPeriod {
+    /// 1 day
+    Day,
+    /// 3 days
+    ThreeDays,
+    /// 7 days
+    Week,
+    /// 14 days
+    TwoWeeks,
+    /// 30 days
+    Month,
+}
+
+impl VotingPeriod {
+    pub fn seconds(self) -> i64 {
+        match self {
+            VotingPeriod::Day => DAY,
+            VotingPeriod::ThreeDays => 3 * DAY,
+            VotingPeriod::Week => WEEK,
+            VotingPeriod::TwoWeeks => 2 * WEEK,
+            VotingPeriod::Month => 30 * DAY,
+        }
+    }
+}
+
+/// The voting deadline of the commands that create a proposal, given one of two ways.
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct DeadlineArgs {
+    /// When voting ends: a duration from now such as `3d`, `36h` or `1d12h`, an RFC 3339 time
+    /// such as `2025-11-01T12:00:00Z`, or unix seconds
+    #[arg(long)]
+    pub voting_deadline: Option<Deadline>,
+
+    /// When voting ends, as a preset duration from now
+    #[arg(long, value_enum)]
+    pub voting_period: Option<VotingPeriod>,
+}
+
+impl DeadlineArgs {
+    /// The deadline in unix seconds. Durations are counted from `now`, the cluster clock that
+    /// the program checks the deadline against when it is available, and the deadline must be
+    /// after it.
+    pub fn resolve(&self, now: i64) -> eyre::Result<i64> {
+        let deadline = match (self.voting_deadline, self.voting_period) {
+            (Some(Deadline::At(deadline)), _) => deadline,
+            (Some(Deadline::In(seconds)), _) => now.saturating_add(seconds),
+            (None, Some(period)) => now + period.seconds(),
+            (None, None) => {
+                return Err(Error::InvalidInput(
+                    "No voting deadline given: pass --voting-deadline or --voting-period"
+                        .to_string(),
+                )
+                .into())
+            }
+        };
+        if deadline <= now {
+            return Err(Error::InvalidInput(format!(
+                "Voting deadline {} is not in the future, the current time is {}",
+                format_timestamp(deadline),
+                format_timestamp(now)
+            ))
+            .into());
+        }
+        Ok(deadline)
+    }
+}
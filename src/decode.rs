@@ -0,0 +1,287 @@
+use borsh::BorshDeserialize;
+use colored::Colorize;
+use fortis_sdk::state::VaultTransactionMessage;
+use serde::Serialize;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_loader_v3_interface::instruction::UpgradeableLoaderInstruction;
+use solana_message::{AddressLookupTableAccount, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_system_interface::instruction::SystemInstruction;
+use spl_associated_token_account_interface::instruction::AssociatedTokenAccountInstruction;
+
+use crate::error::Error;
+use crate::info;
+
+const MEMO_PROGRAM_ID: Pubkey = solana_sdk::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+const MEMO_V1_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+
+/// A Fortis instruction of a transaction message.
+pub struct FortisInstruction {
+    /// Name of the instruction, or `Unknown`
+    pub name: &'static str,
+    pub multisig: Option<Pubkey>,
+}
+
+/// Finds the instructions of `message` calling the Fortis program `program_id`.
+pub fn find_fortis_instructions(
+    message: &VersionedMessage,
+    program_id: &Pubkey,
+) -> Vec<FortisInstruction> {
+    let keys = message.static_account_keys();
+    message
+        .instructions()
+        .iter()
+        .filter(|ix| keys.get(ix.program_id_index as usize) == Some(program_id))
+        .map(|ix| {
+            let name = match ix.data.first() {
+                Some(0) => "MultisigCreate",
+                Some(1) => "ProposalCreate",
+                Some(2) => "ProposalApprove",
+                Some(3) => "ProposalExecute",
+                Some(4) => "ProposalAccountsClose",
+                _ => "Unknown",
+            };
+            // The multisig is the first account of every instruction but `multisig_create`,
+            // where it comes second.
+            let position = if ix.data.first() == Some(&0) { 1 } else { 0 };
+            let multisig = ix
+                .accounts
+                .get(position)
+                .and_then(|index| keys.get(*index as usize))
+                .copied();
+            FortisInstruction { name, multisig }
+        })
+        .collect()
+}
+
+/// An instruction of a vault transaction, with its accounts resolved.
+#[derive(Serialize)]
+pub struct DecodedInstruction {
+    /// Name of the program, or `Unknown`
+    pub program: &'static str,
+    pub program_id: String,
+    pub accounts: Vec<DecodedAccount>,
+    /// Instruction and arguments, when the program is known and the data parses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<String>,
+    /// Raw instruction data, hex encoded
+    pub data: String,
+}
+
+#[derive(Serialize)]
+pub struct DecodedAccount {
+    pub address: String,
+    pub signer: bool,
+    pub writable: bool,
+}
+
+/// Resolves the accounts of every instruction in `message`, loading addresses from
+/// `lookup_tables` (as returned by `get_necessary_alt`), and decodes the instruction data of the
+/// programs we know about.
+pub fn decode_vault_message(
+    message: &VaultTransactionMessage,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Vec<DecodedInstruction> {
+    // Same layout as a v0 message: static keys, then the writable addresses of every lookup,
+    // then the readonly ones.
+    let mut accounts: Vec<DecodedAccount> = message
+        .account_keys
+        .iter()
+        .enumerate()
+        .map(|(index, key)| DecodedAccount {
+            address: key.to_string(),
+            signer: message.is_signer_index(index),
+            writable: message.is_static_writable_index(index),
+        })
+        .collect();
+
+    let lookups = || {
+        message.address_table_lookups.iter().filter_map(|lookup| {
+            lookup_tables
+                .iter()
+                .find(|table| table.key == lookup.account_key)
+                .map(|table| (lookup, table))
+        })
+    };
+    for (lookup, table) in lookups() {
+        for index in lookup.writable_indexes.iter() {
+            accounts.push(loaded_account(table, *index, true));
+        }
+    }
+    for (lookup, table) in lookups() {
+        for index in lookup.readonly_indexes.iter() {
+            accounts.push(loaded_account(table, *index, false));
+        }
+    }
+
+    message
+        .instructions
+        .iter()
+        .map(|ix| {
+            let program_id = message
+                .account_keys
+                .get(ix.program_id_index as usize)
+                .copied()
+                .unwrap_or_default();
+            let (program, instruction) = decode_instruction(&program_id, &ix.data);
+
+            DecodedInstruction {
+                program,
+                program_id: program_id.to_string(),
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|index| match accounts.get(*index as usize) {
+                        Some(account) => DecodedAccount {
+                            address: account.address.clone(),
+                            signer: account.signer,
+                            writable: account.writable,
+                        },
+                        None => DecodedAccount {
+                            address: format!("<missing account #{index}>"),
+                            signer: false,
+                            writable: false,
+                        },
+                    })
+                    .collect(),
+                instruction,
+                data: ix.data.iter().map(|byte| format!("{byte:02x}")).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Checks that the header of `message` fits its account keys and that every instruction refers to
+/// accounts the message has, so that a malformed message is refused before it is proposed rather
+/// than when the proposal is executed.
+pub fn validate_vault_message(message: &VaultTransactionMessage) -> Result<(), Error> {
+    let invalid =
+        |reason: String| Error::InvalidInput(format!("Invalid transaction message: {reason}"));
+    let num_keys = message.account_keys.len();
+    let num_signers = usize::from(message.num_signers);
+    if num_signers > num_keys {
+        return Err(invalid(format!(
+            "it requires {num_signers} signers but has only {num_keys} account keys"
+        )));
+    }
+    if message.num_writable_signers > message.num_signers {
+        return Err(invalid(format!(
+            "it has {} writable signers but only {num_signers} signers",
+            message.num_writable_signers
+        )));
+    }
+    if usize::from(message.num_writable_non_signers) > num_keys - num_signers {
+        return Err(invalid(format!(
+            "it has {} writable non-signers but only {} non-signer account keys",
+            message.num_writable_non_signers,
+            num_keys - num_signers
+        )));
+    }
+
+    let num_accounts = num_keys
+        + message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+            .sum::<usize>();
+    for (index, ix) in message.instructions.iter().enumerate() {
+        // Programs cannot be loaded from a lookup table.
+        if usize::from(ix.program_id_index) >= num_keys {
+            return Err(invalid(format!(
+                "instruction #{} calls account #{} but there are only {num_keys} account keys",
+                index + 1,
+                ix.program_id_index
+            )));
+        }
+        if let Some(account) = ix
+            .accounts
+            .iter()
+            .find(|account| usize::from(**account) >= num_accounts)
+        {
+            return Err(invalid(format!(
+                "instruction #{} uses account #{account} but there are only {num_accounts} accounts",
+                index + 1
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn loaded_account(table: &AddressLookupTableAccount, index: u8, writable: bool) -> DecodedAccount {
+    DecodedAccount {
+        address: table
+            .addresses
+            .get(index as usize)
+            .map(|address| address.to_string())
+            .unwrap_or_else(|| format!("<missing lookup #{index} in {}>", table.key)),
+        signer: false,
+        writable,
+    }
+}
+
+/// Program name and, when the data parses, the instruction with its arguments.
+fn decode_instruction(program_id: &Pubkey, data: &[u8]) -> (&'static str, Option<String>) {
+    if *program_id == solana_system_interface::program::ID {
+        let ix = bincode::deserialize::<SystemInstruction>(data).ok();
+        ("System Program", ix.map(|ix| format!("{ix:?}")))
+    } else if *program_id == spl_token_interface::ID {
+        let ix = spl_token_interface::instruction::TokenInstruction::unpack(data).ok();
+        ("SPL Token", ix.map(|ix| format!("{ix:?}")))
+    } else if *program_id == spl_token_2022_interface::ID {
+        let ix = spl_token_2022_interface::instruction::TokenInstruction::unpack(data).ok();
+        ("SPL Token-2022", ix.map(|ix| format!("{ix:?}")))
+    } else if *program_id == spl_associated_token_account_interface::program::ID {
+        // An empty payload is the original `Create`.
+        let ix = if data.is_empty() {
+            Some(AssociatedTokenAccountInstruction::Create)
+        } else {
+            AssociatedTokenAccountInstruction::try_from_slice(data).ok()
+        };
+        ("Associated Token Account", ix.map(|ix| format!("{ix:?}")))
+    } else if *program_id == solana_sdk_ids::bpf_loader_upgradeable::ID {
+        let ix = bincode::deserialize::<UpgradeableLoaderInstruction>(data).ok();
+        ("BPF Upgradeable Loader", ix.map(|ix| format!("{ix:?}")))
+    } else if *program_id == solana_compute_budget_interface::ID {
+        let ix = ComputeBudgetInstruction::try_from_slice(data).ok();
+        ("Compute Budget", ix.map(|ix| format!("{ix:?}")))
+    } else if *program_id == MEMO_PROGRAM_ID || *program_id == MEMO_V1_PROGRAM_ID {
+        let memo = std::str::from_utf8(data).ok();
+        ("Memo", memo.map(|memo| format!("Memo({memo:?})")))
+    } else {
+        ("Unknown", None)
+    }
+}
+
+/// Prints decoded instructions as part of a review screen.
+pub fn print_instructions(instructions: &[DecodedInstruction]) {
+    info!(
+        "{}",
+        format!("Instructions ({}):", instructions.len()).bold()
+    );
+    for (index, ix) in instructions.iter().enumerate() {
+        info!();
+        info!(
+            "  #{} {} {}",
+            index + 1,
+            ix.program.cyan(),
+            format!("({})", ix.program_id).dimmed()
+        );
+        match &ix.instruction {
+            Some(instruction) => info!("     {}", instruction.bright_white()),
+            None => info!("     Data: {}", ix.data.yellow()),
+        }
+        for account in &ix.accounts {
+            match (account.signer, account.writable) {
+                (true, true) => info!(
+                    "       • {} {}",
+                    account.address,
+                    "signer, writable".dimmed()
+                ),
+                (true, false) => info!("       • {} {}", account.address, "signer".dimmed()),
+                (false, true) => info!("       • {} {}", account.address, "writable".dimmed()),
+                (false, false) => info!("       • {}", account.address),
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+use fortis_sdk::error::ClientError as FortisClientError;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+
+/// Exit codes of the CLI, stable so that scripts can tell failures apart.
+pub mod exit_code {
+    /// Any error without a more specific code
+    pub const OTHER: u8 = 1;
+    /// A malformed argument, also used by clap for usage errors
+    pub const INVALID_INPUT: u8 = 2;
+    /// The confirmation prompt was declined
+    pub const ABORTED: u8 = 3;
+    /// The RPC node could not be reached or returned an error
+    pub const RPC: u8 = 4;
+    /// An account the command needs does not exist
+    pub const ACCOUNT_NOT_FOUND: u8 = 5;
+    /// An account or message could not be decoded
+    pub const DESERIALIZATION: u8 = 6;
+    /// The transaction failed in simulation, before being sent
+    pub const SIMULATION: u8 = 7;
+    /// The transaction landed and failed, or expired before landing
+    pub const PROGRAM: u8 = 8;
+}
+
+/// Errors the CLI reports with a dedicated exit code. Anything else exits with
+/// [`exit_code::OTHER`].
+#[derive(Debug)]
+pub enum Error {
+    InvalidInput(String),
+    Rpc(String),
+    AccountNotFound(String),
+    Deserialization(String),
+    Aborted,
+    /// Carries the program logs of the failed simulation, which the CLI prints before the error.
+    Simulation {
+        message: String,
+        logs: Vec<String>,
+    },
+    Program(String),
+}
+
+impl Error {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::InvalidInput(_) => exit_code::INVALID_INPUT,
+            Error::Rpc(_) => exit_code::RPC,
+            Error::AccountNotFound(_) => exit_code::ACCOUNT_NOT_FOUND,
+            Error::Deserialization(_) => exit_code::DESERIALIZATION,
+            Error::Aborted => exit_code::ABORTED,
+            Error::Simulation { .. } => exit_code::SIMULATION,
+            Error::Program(_) => exit_code::PROGRAM,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidInput(msg)
+            | Error::Rpc(msg)
+            | Error::AccountNotFound(msg)
+            | Error::Deserialization(msg)
+            | Error::Simulation { message: msg, .. }
+            | Error::Program(msg) => f.write_str(msg),
+            Error::Aborted => f.write_str("Aborted"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Exit code for an error returned by a command. RPC client errors that were passed on with `?`
+/// are classified here, so that commands do not have to map each of them.
+pub fn exit_code(report: &eyre::Report) -> u8 {
+    if let Some(err) = report.downcast_ref::<Error>() {
+        return err.exit_code();
+    }
+    if let Some(err) = report.downcast_ref::<FortisClientError>() {
+        return match err {
+            FortisClientError::Client(err) => client_exit_code(err),
+            FortisClientError::InvalidTransactionMessage => exit_code::INVALID_INPUT,
+            FortisClientError::DeserializationError
+            | FortisClientError::InvalidAddressLookupTableAccount => exit_code::DESERIALIZATION,
+        };
+    }
+    if let Some(err) = report.downcast_ref::<ClientError>() {
+        return client_exit_code(err);
+    }
+    exit_code::OTHER
+}
+
+/// Missing accounts are not client errors: accounts are read with
+/// [`get_account_data`](crate::utils::get_account_data), which reports them as
+/// [`Error::AccountNotFound`].
+fn client_exit_code(err: &ClientError) -> u8 {
+    match err.kind() {
+        ClientErrorKind::TransactionError(_) => exit_code::PROGRAM,
+        _ => exit_code::RPC,
+    }
+}
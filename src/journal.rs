@@ -0,0 +1,187 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction};
+
+use crate::config::Config;
+use crate::info;
+use crate::utils::now;
+
+/// Name of the journal, next to the config file.
+pub const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// Entries kept when the journal is rewritten, the oldest are dropped first.
+const MAX_ENTRIES: usize = 1000;
+
+/// What is known about the outcome of a journaled transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalStatus {
+    /// Sent, but the CLI stopped before learning the outcome
+    Pending,
+    Confirmed,
+    Failed,
+    /// Its blockhash or nonce expired before it landed, it never will
+    Expired,
+}
+
+/// A transaction sent by the CLI.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub signature: String,
+    /// Subcommand that sent it, e.g. `proposal-approve`
+    pub command: String,
+    pub rpc_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_index: Option<u64>,
+    pub blockhash: String,
+    /// Durable nonce account, when the transaction uses one instead of a recent blockhash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// Unix timestamp
+    pub sent_at: i64,
+    pub status: JournalStatus,
+}
+
+/// Records the transactions sent by a command, so that `tx-status` and `pending` can report
+/// their outcome if the CLI is interrupted while waiting for confirmation.
+pub struct Journal {
+    /// `None` when nothing is recorded
+    path: Option<PathBuf>,
+    command: &'static str,
+    multisig: Option<Pubkey>,
+    transaction_index: Option<u64>,
+}
+
+impl Journal {
+    pub fn new(
+        config: &Config,
+        command: &'static str,
+        multisig: Option<Pubkey>,
+        transaction_index: Option<u64>,
+    ) -> Self {
+        Self {
+            path: Some(Self::path(config)),
+            command,
+            multisig,
+            transaction_index,
+        }
+    }
+
+    /// A journal that records nothing, for transactions sent through [`crate::FortisClient`],
+    /// which has no config file to keep a journal next to.
+    pub fn disabled() -> Self {
+        Self {
+            path: None,
+            command: "",
+            multisig: None,
+            transaction_index: None,
+        }
+    }
+
+    pub fn path(config: &Config) -> PathBuf {
+        config.path.with_file_name(JOURNAL_FILE)
+    }
+
+    /// Adds `transaction` as pending, right before it is sent. A journal that cannot be written
+    /// is reported but does not stop the transaction.
+    pub fn record(
+        &self,
+        rpc_url: String,
+        transaction: &VersionedTransaction,
+        nonce: Option<Pubkey>,
+    ) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let entry = JournalEntry {
+            signature: transaction.signatures[0].to_string(),
+            command: self.command.to_string(),
+            rpc_url,
+            multisig: self.multisig.map(|multisig| multisig.to_string()),
+            transaction_index: self.transaction_index,
+            blockhash: transaction.message.recent_blockhash().to_string(),
+            nonce: nonce.map(|nonce| nonce.to_string()),
+            sent_at: now(),
+            status: JournalStatus::Pending,
+        };
+        if let Err(err) = append(path, &entry) {
+            info!(
+                "{} {:#}",
+                "Warning: transaction not journaled:".yellow(),
+                err
+            );
+        }
+    }
+
+    /// Records the outcome of a transaction sent by this command.
+    pub fn set_status(&self, signature: &str, status: JournalStatus) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Err(err) = set_status(path, signature, status) {
+            info!("{} {:#}", "Warning: journal not updated:".yellow(), err);
+        }
+    }
+}
+
+/// Reads the journal, oldest entry first. A missing journal is empty.
+pub fn load(path: &Path) -> eyre::Result<Vec<JournalEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read journal {}", path.display()))?;
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .wrap_err_with(|| format!("Failed to parse journal {}", path.display()))
+        })
+        .collect()
+}
+
+/// Updates the status of the entry for `signature`, if there is one.
+pub fn set_status(path: &Path, signature: &str, status: JournalStatus) -> eyre::Result<()> {
+    let mut entries = load(path)?;
+    let Some(entry) = entries
+        .iter_mut()
+        .find(|entry| entry.signature == signature)
+    else {
+        return Ok(());
+    };
+    if entry.status == status {
+        return Ok(());
+    }
+    entry.status = status;
+
+    let skip = entries.len().saturating_sub(MAX_ENTRIES);
+    let mut contents = String::new();
+    for entry in &entries[skip..] {
+        contents.push_str(&serde_json::to_string(entry)?);
+        contents.push('\n');
+    }
+    fs::write(path, contents)
+        .wrap_err_with(|| format!("Failed to write journal {}", path.display()))
+}
+
+fn append(path: &Path, entry: &JournalEntry) -> eyre::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .wrap_err_with(|| format!("Failed to open journal {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+        .wrap_err_with(|| format!("Failed to write journal {}", path.display()))
+}
@@ -0,0 +1,45 @@
+//! Fortis multisig CLI and client library.
+//!
+//! [`FortisClient`] builds, sends and reads Fortis multisig transactions from Rust. The
+//! `fortis-multisig-cli` binary is a thin layer over it: the [`command`] modules parse the
+//! arguments, print a review screen and send through the [`pipeline`].
+//!
+//! ```no_run
+//! use fortis_multisig_cli::FortisClient;
+//! use solana_client::nonblocking::rpc_client::RpcClient;
+//! # async fn propose(
+//! #     member: &dyn solana_sdk::signature::Signer,
+//! #     multisig: solana_sdk::pubkey::Pubkey,
+//! #     recipient: solana_sdk::pubkey::Pubkey,
+//! # ) -> eyre::Result<()> {
+//! let client = FortisClient::new(
+//!     RpcClient::new("https://api.devnet.solana.com".to_string()),
+//!     fortis_sdk::pda::FORTIS_PROGRAM_ID,
+//! );
+//! let message = client.native_transfer_message(&multisig, &recipient, 1_000_000)?;
+//! let deadline = fortis_multisig_cli::utils::now() + 3 * 86_400;
+//! let created = client
+//!     .create_proposal(member, &multisig, &message, deadline)
+//!     .await?;
+//! client
+//!     .approve(member, &multisig, created.transaction_index)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod address;
+pub mod client;
+pub mod command;
+pub mod compute_budget;
+pub mod config;
+pub mod deadline;
+pub mod decode;
+pub mod error;
+pub mod journal;
+pub mod output;
+pub mod pipeline;
+pub mod program_error;
+pub mod utils;
+
+pub use client::{FortisClient, MultisigCreated, ProposalSent, ProposalState};
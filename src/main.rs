@@ -1,28 +1,36 @@
+use std::process::ExitCode;
+
 use clap::Parser;
-use command::Command;
-mod command;
-pub mod utils;
+use colored::Colorize;
+use fortis_multisig_cli::command::{warn_deprecated_flags, Cli, Command};
+use fortis_multisig_cli::config::Config;
+use fortis_multisig_cli::{error, info, output};
 
-#[derive(Parser)]
-struct App {
-    #[command(subcommand)]
-    command: Command,
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            match err.downcast_ref::<error::Error>() {
+                Some(error::Error::Aborted) => info!("OK, aborting."),
+                Some(error::Error::Simulation { logs, .. }) if !logs.is_empty() => {
+                    info!("Simulation logs:\n\n{}\n", logs.join("\n").yellow());
+                    eprintln!("Error: {err:?}");
+                }
+                _ => eprintln!("Error: {err:?}"),
+            }
+            ExitCode::from(error::exit_code(&err))
+        }
+    }
 }
 
-#[tokio::main]
-async fn main() -> eyre::Result<()> {
-    let app = App::parse();
+async fn run() -> eyre::Result<()> {
+    warn_deprecated_flags(&std::env::args().collect::<Vec<_>>());
+    let app = Cli::parse();
+    output::init(app.global.output);
 
-    match app.command {
-        Command::MultisigCreate(command) => command.execute().await,
-        Command::ProposalApprove(command) => command.execute().await,
-        Command::ProposalExecute(command) => command.execute().await,
-        Command::ProposalCreate(command) => command.execute().await,
-        Command::ProposalAccountsClose(command) => command.execute().await,
-        Command::InitiateTransfer(command) => command.execute().await,
-        Command::InitiateNativeTransfer(command) => command.execute().await,
-        Command::InitiateProgramUpgrade(command) => command.execute().await,
-        Command::DisplayVault(command) => command.execute().await,
-        Command::DisplayMultisig(command) => command.execute().await,
-    }
+    let is_config_command = matches!(app.command, Command::Config(_));
+    let config = Config::load(app.global, is_config_command)?;
+
+    app.command.execute(&config).await
 }
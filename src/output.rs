@@ -0,0 +1,104 @@
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable, colored text
+    #[default]
+    Text,
+    /// Pretty-printed JSON on stdout
+    Json,
+    /// Single-line JSON on stdout
+    JsonCompact,
+}
+
+impl OutputFormat {
+    pub fn is_json(self) -> bool {
+        self != OutputFormat::Text
+    }
+}
+
+/// Sets the output format for the rest of the process. Called once from `main`.
+pub fn init(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+pub fn format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Prints human oriented text (review screens, progress). In JSON modes it goes to stderr so
+/// stdout only ever carries the JSON document.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::output::format().is_json() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Prints `value` as JSON in JSON modes, otherwise renders it with `text`.
+pub fn print<T: Serialize>(value: &T, text: impl FnOnce(&T)) -> eyre::Result<()> {
+    match format() {
+        OutputFormat::Text => text(value),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::JsonCompact => println!("{}", serde_json::to_string(value)?),
+    }
+    Ok(())
+}
+
+/// Result of every command that sends a transaction.
+#[derive(Serialize)]
+pub struct TransactionOutput {
+    pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_index: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proposal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<String>,
+}
+
+/// Result of `--dry-run`: what the transaction would have done, nothing was sent.
+#[derive(Serialize)]
+pub struct SimulationOutput {
+    pub err: Option<String>,
+    /// What `err` means, when the failing program is Fortis, System, SPL Token or the loader
+    pub explanation: Option<String>,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+    pub account_changes: Vec<AccountChange>,
+}
+
+/// Before and after state of a writable account touched by a simulated transaction.
+#[derive(Serialize)]
+pub struct AccountChange {
+    pub address: String,
+    pub lamports_before: u64,
+    pub lamports_after: u64,
+    pub data_len_before: usize,
+    pub data_len_after: usize,
+    pub owner_before: Option<String>,
+    pub owner_after: Option<String>,
+}
+
+/// Result of `--sign-only`: the message and the signatures made on this machine.
+#[derive(Serialize)]
+pub struct SignOnlyOutput {
+    pub blockhash: String,
+    /// Serialized message, base58 encoded
+    pub message: String,
+    /// `PUBKEY=SIGNATURE` for every signer available here
+    pub signers: Vec<String>,
+    /// Signers that still have to sign
+    pub absent: Vec<String>,
+}
@@ -0,0 +1,411 @@
+use clap::Args;
+use colored::Colorize;
+use eyre::eyre;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_instruction::Instruction;
+use solana_message::AddressLookupTableAccount;
+use solana_sdk::{
+    hash::Hash,
+    message::{v0::Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::VersionedTransaction,
+};
+
+use crate::address::{parse_address, Address};
+use crate::client::FortisClient;
+use crate::compute_budget::{ComputeBudget, ComputeBudgetArgs};
+use crate::config::Config;
+use crate::error::Error;
+use crate::info;
+use crate::journal::Journal;
+use crate::utils::{
+    confirm, create_signer, get_blockhash, is_index_taken, now, print_sign_only,
+    refresh_transaction_index, send_and_confirm_transaction, simulate_transaction, unique_signers,
+    with_nonce, MAX_INDEX_RETRIES,
+};
+
+/// Flags shared by every command that builds and sends a transaction.
+#[derive(Args)]
+pub struct TransactionArgs {
+    #[command(flatten)]
+    compute_budget: ComputeBudgetArgs,
+
+    /// Simulate the transaction and print logs, compute units and account changes without sending it
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Sign with the keypairs available here and print the signatures instead of sending.
+    /// Signers held elsewhere can be given as a pubkey
+    #[arg(long, requires = "blockhash", conflicts_with = "dry_run")]
+    sign_only: bool,
+
+    /// Use this blockhash instead of fetching the latest one
+    #[arg(long)]
+    blockhash: Option<Hash>,
+
+    /// Durable nonce account to use instead of a recent blockhash, for signing that takes longer
+    /// than a blockhash lives
+    #[arg(long, value_parser = parse_address)]
+    nonce: Option<Address>,
+
+    /// Authority of the nonce account. Defaults to the keypair
+    #[arg(long, requires = "nonce")]
+    nonce_authority: Option<String>,
+
+    /// Keypair paying the transaction fees, so that the member key needs no SOL. Defaults to the
+    /// keypair
+    #[arg(long, alias = "fee-payer-keypair")]
+    fee_payer: Option<String>,
+}
+
+/// Builds, prices, signs and sends the transaction of a command: the command supplies its
+/// instructions, the pipeline adds the compute budget and durable nonce instructions, asks for
+/// confirmation, then simulates (`--dry-run`), prints signatures (`--sign-only`) or sends it.
+pub struct TransactionPipeline<'a> {
+    config: &'a Config,
+    command: &'static str,
+    /// `None` when signing offline without one
+    rpc_url: Option<String>,
+    client: FortisClient,
+    member: Box<dyn Signer>,
+    fee_payer: Option<Box<dyn Signer>>,
+    nonce_authority: Option<Box<dyn Signer>>,
+    extra_signers: Vec<Box<dyn Signer>>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    multisig: Option<Pubkey>,
+    compute_budget: ComputeBudgetArgs,
+    dry_run: bool,
+    sign_only: bool,
+    blockhash: Option<Hash>,
+    nonce: Option<Pubkey>,
+}
+
+impl<'a> TransactionPipeline<'a> {
+    /// `command` is the subcommand name recorded in the journal, e.g. `proposal-approve`.
+    /// `keypair` is the member signing the Fortis instruction, the profile keypair by default.
+    pub fn new(
+        config: &'a Config,
+        command: &'static str,
+        rpc_url: Option<String>,
+        keypair: Option<String>,
+        args: TransactionArgs,
+    ) -> eyre::Result<Self> {
+        let TransactionArgs {
+            compute_budget,
+            dry_run,
+            sign_only,
+            blockhash,
+            nonce,
+            nonce_authority,
+            fee_payer,
+        } = args;
+
+        let member = create_signer(config.keypair(keypair)?, sign_only)?;
+        let fee_payer = fee_payer
+            .map(|path| create_signer(path, sign_only))
+            .transpose()?;
+        let nonce_authority = nonce_authority
+            .map(|path| create_signer(path, sign_only))
+            .transpose()?;
+        let nonce = nonce.map(|nonce| config.pubkey(&nonce)).transpose()?;
+
+        // Signing offline needs no cluster, so no URL either.
+        let rpc_url = match config.rpc_url(rpc_url) {
+            Err(_) if sign_only => None,
+            rpc_url => Some(rpc_url?),
+        };
+        let client = config.client(rpc_url.clone().unwrap_or_default());
+
+        Ok(Self {
+            config,
+            command,
+            rpc_url,
+            client,
+            member,
+            fee_payer,
+            nonce_authority,
+            extra_signers: Vec::new(),
+            lookup_tables: Vec::new(),
+            multisig: None,
+            compute_budget,
+            dry_run,
+            sign_only,
+            blockhash,
+            nonce,
+        })
+    }
+
+    /// The multisig the transaction acts on, recorded in the journal. Required by
+    /// [`Self::send_proposal`].
+    pub fn multisig(mut self, multisig: Pubkey) -> Self {
+        self.multisig = Some(multisig);
+        self
+    }
+
+    /// Another signer, besides the member, fee payer and nonce authority.
+    pub fn extra_signer(mut self, signer: Box<dyn Signer>) -> Self {
+        self.extra_signers.push(signer);
+        self
+    }
+
+    /// Address lookup tables to compile the message with.
+    pub fn lookup_tables(mut self, lookup_tables: Vec<AddressLookupTableAccount>) -> Self {
+        self.lookup_tables = lookup_tables;
+        self
+    }
+
+    /// Builds the Fortis instructions and reads the accounts the command needs.
+    pub fn client(&self) -> &FortisClient {
+        &self.client
+    }
+
+    pub fn rpc_client(&self) -> &RpcClient {
+        self.client.rpc_client()
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.client.program_id()
+    }
+
+    /// The member signing the Fortis instruction.
+    pub fn member(&self) -> Pubkey {
+        self.member.pubkey()
+    }
+
+    pub fn fee_payer(&self) -> Pubkey {
+        self.fee_payer
+            .as_ref()
+            .map_or_else(|| self.member(), |fee_payer| fee_payer.pubkey())
+    }
+
+    fn nonce_authority(&self) -> Pubkey {
+        self.nonce_authority
+            .as_ref()
+            .map_or_else(|| self.member(), |authority| authority.pubkey())
+    }
+
+    /// With `--sign-only` there is no cluster to read accounts from.
+    pub fn is_sign_only(&self) -> bool {
+        self.sign_only
+    }
+
+    /// Index of the proposal to create on the multisig: the next one, read from the cluster, or
+    /// `given` with `--sign-only`, which has no cluster to read it from.
+    pub async fn next_transaction_index(&self, given: Option<u64>) -> eyre::Result<u64> {
+        let multisig = self
+            .multisig
+            .ok_or_else(|| eyre!("The multisig must be set to create a proposal"))?;
+        match (self.sign_only, given) {
+            (true, Some(transaction_index)) => Ok(transaction_index),
+            (true, None) => Err(Error::InvalidInput(
+                "--sign-only cannot read the next transaction index from the cluster: pass it \
+                 with --transaction-index"
+                    .to_string(),
+            )
+            .into()),
+            (false, _) => self.client.next_transaction_index(&multisig).await,
+        }
+    }
+
+    /// The unix time voting deadlines are counted from and checked against: the cluster clock,
+    /// as the program does, or the local clock with `--sign-only`.
+    pub async fn now(&self) -> eyre::Result<i64> {
+        if self.sign_only {
+            Ok(now())
+        } else {
+            self.client.cluster_time().await
+        }
+    }
+
+    /// Prints the top of the review screen: `title`, the cluster and the keys involved. The
+    /// command follows with its own parameters.
+    pub fn print_review(&self, title: &str) {
+        info!();
+        info!("{}", title.yellow());
+        info!();
+        info!(
+            "RPC Cluster URL:   {}",
+            self.rpc_url.as_deref().unwrap_or("none, signing offline")
+        );
+        info!("Program ID:        {}", self.program_id());
+        if let Some(nonce) = self.nonce {
+            info!("Durable Nonce:     {}", nonce);
+        }
+        info!("Your Public Key:       {}", self.member());
+        if self.fee_payer() != self.member() {
+            info!("Fee Payer:         {}", self.fee_payer());
+        }
+        info!();
+        info!("⚙️ Config Parameters");
+    }
+
+    /// Runs the transaction made of `instructions`. Returns the signature once confirmed, or
+    /// `None` when it was only simulated or signed.
+    pub async fn send(
+        &self,
+        instructions: Vec<Instruction>,
+        transaction_index: Option<u64>,
+    ) -> eyre::Result<Option<String>> {
+        let compute_budget = self.resolve(&instructions).await?;
+        self.confirm()?;
+        let blockhash = get_blockhash(self.rpc_client(), self.blockhash, self.nonce).await?;
+        self.sign_and_send(&instructions, &compute_budget, blockhash, transaction_index)
+            .await
+    }
+
+    /// Runs the transaction creating proposal `transaction_index`, built by `instructions` for a
+    /// given index. The index is read again right before sending and the proposal is rebuilt
+    /// when another member took it in the meantime. Returns the signature and the index used.
+    pub async fn send_proposal(
+        &self,
+        transaction_index: u64,
+        instructions: impl Fn(u64) -> Vec<Instruction>,
+    ) -> eyre::Result<Option<(String, u64)>> {
+        let multisig = self
+            .multisig
+            .ok_or_else(|| eyre!("The multisig must be set to create a proposal"))?;
+
+        let compute_budget = self.resolve(&instructions(transaction_index)).await?;
+        self.confirm()?;
+        let blockhash = get_blockhash(self.rpc_client(), self.blockhash, self.nonce).await?;
+
+        let mut transaction_index = transaction_index;
+        let mut retries = 0;
+        loop {
+            if !self.sign_only {
+                transaction_index =
+                    refresh_transaction_index(self.rpc_client(), &multisig, transaction_index)
+                        .await?;
+            }
+
+            match self
+                .sign_and_send(
+                    &instructions(transaction_index),
+                    &compute_budget,
+                    blockhash,
+                    Some(transaction_index),
+                )
+                .await
+            {
+                Ok(signature) => {
+                    return Ok(signature.map(|signature| (signature, transaction_index)))
+                }
+                // Another member's proposal landed first with the same index.
+                Err(_)
+                    if retries < MAX_INDEX_RETRIES
+                        && is_index_taken(
+                            self.rpc_client(),
+                            &multisig,
+                            transaction_index,
+                            &self.member(),
+                            &self.program_id(),
+                        )
+                        .await =>
+                {
+                    retries += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// `instructions` preceded by the durable nonce advance and the compute budget.
+    fn with_budget(
+        &self,
+        instructions: &[Instruction],
+        compute_unit_limit: u32,
+        compute_unit_price: u64,
+    ) -> Vec<Instruction> {
+        let mut all = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price),
+        ];
+        all.extend_from_slice(instructions);
+        with_nonce(self.nonce, &self.nonce_authority(), all)
+    }
+
+    async fn resolve(&self, instructions: &[Instruction]) -> eyre::Result<ComputeBudget> {
+        let compute_budget = self
+            .compute_budget
+            .resolve(
+                self.config,
+                self.rpc_client(),
+                &self.fee_payer(),
+                &self.lookup_tables,
+                self.sign_only,
+                |compute_unit_limit, compute_unit_price| {
+                    self.with_budget(instructions, compute_unit_limit, compute_unit_price)
+                },
+            )
+            .await?;
+        compute_budget.print();
+        Ok(compute_budget)
+    }
+
+    fn confirm(&self) -> eyre::Result<()> {
+        if !self.dry_run {
+            confirm(self.config)?;
+        }
+        info!();
+        Ok(())
+    }
+
+    async fn sign_and_send(
+        &self,
+        instructions: &[Instruction],
+        compute_budget: &ComputeBudget,
+        blockhash: Hash,
+        transaction_index: Option<u64>,
+    ) -> eyre::Result<Option<String>> {
+        let message = Message::try_compile(
+            &self.fee_payer(),
+            &self.with_budget(
+                instructions,
+                compute_budget.unit_limit,
+                compute_budget.unit_price,
+            ),
+            &self.lookup_tables,
+            blockhash,
+        )?;
+
+        if self.dry_run {
+            simulate_transaction(
+                VersionedMessage::V0(message),
+                self.rpc_client(),
+                &self.program_id(),
+            )
+            .await?;
+            return Ok(None);
+        }
+
+        let signers = unique_signers(
+            [
+                Some(&*self.member),
+                self.fee_payer.as_deref(),
+                self.nonce_authority.as_deref(),
+            ]
+            .into_iter()
+            .chain(self.extra_signers.iter().map(|signer| Some(&**signer))),
+        );
+
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &signers)?;
+
+        if self.sign_only {
+            print_sign_only(&transaction)?;
+            return Ok(None);
+        }
+
+        let journal = Journal::new(self.config, self.command, self.multisig, transaction_index);
+        let signature = send_and_confirm_transaction(
+            &transaction,
+            self.rpc_client(),
+            &signers,
+            &journal,
+            &self.program_id(),
+        )
+        .await?;
+        Ok(Some(signature))
+    }
+}
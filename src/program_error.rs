@@ -0,0 +1,83 @@
+use solana_instruction::error::InstructionError;
+use solana_message::VersionedMessage;
+use solana_sdk::{pubkey::Pubkey, transaction::TransactionError};
+
+/// Why a transaction failed, as `<Program> <ErrorName> (0x<code>): <explanation>`, when the
+/// failing program is System, SPL Token or the upgradeable loader. Custom errors of Fortis
+/// (`program_id`) are not explained: the program's error enum is not published with
+/// `fortis_sdk` 1.0.4, and a guessed name is worse than the raw code.
+/// `logs` tell which program failed when the error comes from a CPI, such as a token transfer
+/// run by `ProposalExecute`; without them the program of the failing instruction is assumed.
+pub fn explain(
+    err: &TransactionError,
+    message: &VersionedMessage,
+    logs: &[String],
+    program_id: &Pubkey,
+) -> Option<String> {
+    let TransactionError::InstructionError(index, err) = err else {
+        return None;
+    };
+    let failing_program = failing_program(logs).or_else(|| {
+        let ix = message.instructions().get(*index as usize)?;
+        message
+            .static_account_keys()
+            .get(ix.program_id_index as usize)
+            .copied()
+    })?;
+
+    match err {
+        InstructionError::Custom(code) => explain_custom(&failing_program, *code, program_id),
+        err if failing_program == solana_sdk_ids::bpf_loader_upgradeable::ID => {
+            explain_loader(err).map(|explanation| format!("BPF Upgradeable Loader {explanation}"))
+        }
+        _ => None,
+    }
+}
+
+fn explain_custom(failing_program: &Pubkey, code: u32, program_id: &Pubkey) -> Option<String> {
+    // Left unnamed until the program's error enum or IDL is published.
+    if failing_program == program_id {
+        return None;
+    }
+    let (program, name, explanation) = if *failing_program == solana_system_interface::program::ID {
+        let err = solana_system_interface::error::SystemError::try_from(code).ok()?;
+        ("System", format!("{err:?}"), err.to_string())
+    } else if *failing_program == spl_token_interface::ID {
+        let err = spl_token_interface::error::TokenError::try_from(code).ok()?;
+        ("SPL Token", format!("{err:?}"), err.to_string())
+    } else if *failing_program == spl_token_2022_interface::ID {
+        let err = spl_token_2022_interface::error::TokenError::try_from(code).ok()?;
+        ("SPL Token-2022", format!("{err:?}"), err.to_string())
+    } else {
+        return None;
+    };
+    Some(format!("{program} {name} ({code:#x}): {explanation}"))
+}
+
+/// The upgradeable loader reports its failures as standard instruction errors.
+fn explain_loader(err: &InstructionError) -> Option<String> {
+    let explanation = match err {
+        InstructionError::IncorrectAuthority => {
+            "the upgrade authority of the program or buffer must be the multisig vault"
+        }
+        InstructionError::MissingRequiredSignature => "the upgrade authority did not sign",
+        InstructionError::AccountDataTooSmall => {
+            "the program account is too small for the new build, extend it with `solana program extend` first"
+        }
+        InstructionError::InvalidAccountData => {
+            "the buffer or program account is not in the state the loader expects, e.g. the buffer was already deployed"
+        }
+        InstructionError::Immutable => "the program is immutable and can no longer be upgraded",
+        _ => return None,
+    };
+    Some(format!("{err:?}: {explanation}"))
+}
+
+/// The program whose failure ended the transaction, from the last `Program <ID> failed` log.
+fn failing_program(logs: &[String]) -> Option<Pubkey> {
+    logs.iter().rev().find_map(|log| {
+        let rest = log.strip_prefix("Program ")?;
+        let (program, rest) = rest.split_once(' ')?;
+        rest.starts_with("failed").then(|| program.parse().ok())?
+    })
+}
@@ -1,17 +1,51 @@
+use std::io::IsTerminal;
+use std::time::Duration;
+
+use chrono::{Local, TimeZone};
 use clap_v3::ArgMatches;
 use colored::Colorize;
+use dialoguer::Confirm;
 use eyre::eyre;
+use fortis_sdk::{
+    client::{get_multisig, get_transaction},
+    pda::get_transaction_pda,
+    state::{Proposal, VaultTransactionMessage},
+};
+use indicatif::ProgressBar;
 use solana_client::{
-    client_error::ClientErrorKind,
+    client_error::{ClientError, ClientErrorKind},
     nonblocking::rpc_client::RpcClient,
+    rpc_config::{
+        RpcSendTransactionConfig, RpcSimulateTransactionAccountsConfig,
+        RpcSimulateTransactionConfig, UiAccountEncoding,
+    },
     rpc_request::{RpcError, RpcResponseErrorData},
     rpc_response::RpcSimulateTransactionResult,
 };
-use solana_sdk::{signer::Signer, transaction::VersionedTransaction};
+use solana_commitment_config::CommitmentConfig;
+use solana_instruction::Instruction;
+use solana_message::AddressLookupTableAccount;
+use solana_nonce::state::Data as NonceData;
+use solana_rpc_client_nonce_utils::nonblocking;
+use solana_sdk::{
+    hash::Hash,
+    message::{v0::Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{null_signer::NullSigner, Signer},
+    transaction::{TransactionError, VersionedTransaction},
+};
+use solana_system_interface::instruction::{advance_nonce_account, SystemInstruction};
+use std::str::FromStr;
 
-pub fn create_signer_from_path(
-    keypair_path: String,
-) -> Result<Box<dyn Signer>, Box<dyn std::error::Error>> {
+use crate::config::Config;
+use crate::error::Error;
+use crate::info;
+use crate::journal::{Journal, JournalStatus};
+use crate::output::{self, AccountChange, SignOnlyOutput, SimulationOutput};
+use crate::program_error;
+
+pub fn create_signer_from_path(keypair_path: String) -> eyre::Result<Box<dyn Signer>> {
     let mut wallet_manager = None;
     let matches = ArgMatches::default();
 
@@ -21,35 +55,801 @@ pub fn create_signer_from_path(
         "Keypair",
         &mut wallet_manager,
     )
+    .map_err(|err| Error::InvalidInput(format!("Invalid keypair {}: {}", keypair_path, err)).into())
+}
+
+/// Like [`create_signer_from_path`], but with `--sign-only` a plain pubkey may be given for a
+/// signer whose key is not on this machine. Its signature is left empty, to be collected with
+/// `broadcast --signer`.
+pub fn create_signer(keypair_path: String, sign_only: bool) -> eyre::Result<Box<dyn Signer>> {
+    if sign_only {
+        if let Ok(pubkey) = Pubkey::from_str(&keypair_path) {
+            return Ok(Box::new(NullSigner::new(&pubkey)));
+        }
+    }
+    create_signer_from_path(keypair_path)
+}
+
+/// The signers of a transaction, each key once: the member may also be the fee payer or the
+/// nonce authority. Absent optional signers are skipped.
+pub fn unique_signers<'a>(
+    signers: impl IntoIterator<Item = Option<&'a dyn Signer>>,
+) -> Vec<&'a dyn Signer> {
+    let mut unique: Vec<&dyn Signer> = Vec::new();
+    for signer in signers.into_iter().flatten() {
+        if !unique.iter().any(|s| s.pubkey() == signer.pubkey()) {
+            unique.push(signer);
+        }
+    }
+    unique
+}
+
+/// Asks the user to confirm the reviewed transaction, failing with [`Error::Aborted`] when they
+/// decline. Passes straight away with `--yes`, and errors instead of blocking when there is no
+/// terminal to prompt on.
+pub fn confirm(config: &Config) -> eyre::Result<()> {
+    if config.yes {
+        return Ok(());
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(Error::InvalidInput(
+            "Refusing to prompt for confirmation: stdin is not a terminal. \
+             Pass --yes (or set FORTIS_YES=1) to proceed without confirmation"
+                .to_string(),
+        )
+        .into());
+    }
+    if !Confirm::new()
+        .with_prompt("Do you want to proceed?")
+        .default(false)
+        .interact()?
+    {
+        return Err(Error::Aborted.into());
+    }
+    Ok(())
 }
 
+/// Time between two rebroadcasts of a transaction that is not confirmed yet.
+const REBROADCAST_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Times an expired transaction is re-signed with a new blockhash before giving up.
+const MAX_RESIGN_ATTEMPTS: usize = 3;
+
+/// Consecutive failed status checks tolerated while waiting for confirmation, about a minute.
+const MAX_STATUS_FAILURES: usize = 30;
+
+/// Sends `transaction` and waits until it reaches the commitment of `rpc_client`, rebroadcasting
+/// it while its blockhash is valid. A transaction that expires unconfirmed is re-signed with a new
+/// blockhash, unless `signers` is empty as for a transaction signed elsewhere. Durable nonce
+/// transactions do not expire with a blockhash, they are rebroadcast until the nonce advances.
 pub async fn send_and_confirm_transaction(
     transaction: &VersionedTransaction,
     rpc_client: &RpcClient,
+    signers: &[&dyn Signer],
+    journal: &Journal,
+    program_id: &Pubkey,
 ) -> eyre::Result<String> {
-    match rpc_client.send_and_confirm_transaction(transaction).await {
-        Ok(signature) => {
-            println!(
-                "Transaction confirmed: {}\n\n",
-                signature.to_string().green()
-            );
-            Ok(signature.to_string())
-        }
-        Err(err) => {
-            if let ClientErrorKind::RpcError(RpcError::RpcResponseError {
-                data:
-                    RpcResponseErrorData::SendTransactionPreflightFailure(
-                        RpcSimulateTransactionResult {
-                            logs: Some(logs), ..
+    let progress = ProgressBar::new_spinner();
+    progress.enable_steady_tick(Duration::from_millis(100));
+
+    let result = send_with_retries(
+        transaction,
+        rpc_client,
+        signers,
+        journal,
+        program_id,
+        &progress,
+    )
+    .await;
+    progress.finish_and_clear();
+
+    let signature = result?;
+    info!(
+        "Transaction confirmed: {}\n\n",
+        signature.to_string().green()
+    );
+    Ok(signature.to_string())
+}
+
+/// [`send_and_confirm_transaction`] without the progress spinner and the confirmation message,
+/// for callers of the library that print their own.
+pub async fn send_and_confirm_quietly(
+    transaction: &VersionedTransaction,
+    rpc_client: &RpcClient,
+    signers: &[&dyn Signer],
+    journal: &Journal,
+    program_id: &Pubkey,
+) -> eyre::Result<Signature> {
+    send_with_retries(
+        transaction,
+        rpc_client,
+        signers,
+        journal,
+        program_id,
+        &ProgressBar::hidden(),
+    )
+    .await
+}
+
+async fn send_with_retries(
+    transaction: &VersionedTransaction,
+    rpc_client: &RpcClient,
+    signers: &[&dyn Signer],
+    journal: &Journal,
+    program_id: &Pubkey,
+    progress: &ProgressBar,
+) -> eyre::Result<Signature> {
+    let nonce = durable_nonce(&transaction.message);
+    let mut transaction = transaction.clone();
+    let mut attempts = 0;
+
+    loop {
+        if let Some(signature) = send_until_expired(
+            &transaction,
+            rpc_client,
+            nonce,
+            journal,
+            program_id,
+            progress,
+        )
+        .await?
+        {
+            return Ok(signature);
+        }
+
+        if signers.is_empty() || nonce.is_some() || attempts == MAX_RESIGN_ATTEMPTS {
+            return Err(Error::Program(format!(
+                "Transaction {} expired before it was confirmed",
+                transaction.signatures[0]
+            ))
+            .into());
+        }
+
+        attempts += 1;
+        progress.set_message(format!(
+            "Blockhash expired, re-signing with a new one ({attempts}/{MAX_RESIGN_ATTEMPTS})..."
+        ));
+        let mut message = transaction.message.clone();
+        message.set_recent_blockhash(rpc_client.get_latest_blockhash().await?);
+        transaction = VersionedTransaction::try_new(message, signers)?;
+    }
+}
+
+/// Sends `transaction` and rebroadcasts it until it reaches the commitment, returning its
+/// signature, or until it can no longer land, returning `None`. Its outcome is recorded in
+/// `journal`.
+async fn send_until_expired(
+    transaction: &VersionedTransaction,
+    rpc_client: &RpcClient,
+    nonce: Option<Pubkey>,
+    journal: &Journal,
+    program_id: &Pubkey,
+    progress: &ProgressBar,
+) -> eyre::Result<Option<Signature>> {
+    let signature = transaction.signatures[0];
+    let blockhash = *transaction.message.recent_blockhash();
+    let commitment = rpc_client.commitment();
+
+    journal.record(rpc_client.url(), transaction, nonce);
+
+    progress.set_message("Sending transaction...");
+    // Only the first send runs preflight checks, so a failing transaction stops here with its logs.
+    if let Err(err) = rpc_client
+        .send_transaction_with_config(
+            transaction,
+            RpcSendTransactionConfig {
+                preflight_commitment: Some(commitment.commitment),
+                ..RpcSendTransactionConfig::default()
+            },
+        )
+        .await
+    {
+        // Rejected by the node, as opposed to the node being unreachable.
+        if matches!(*err.kind, ClientErrorKind::RpcError(_)) {
+            journal.set_status(&signature.to_string(), JournalStatus::Failed);
+        }
+        return Err(transaction_error(err, &transaction.message, program_id));
+    }
+
+    progress.set_message(format!(
+        "Waiting for {} confirmation...",
+        commitment.commitment
+    ));
+    let mut rebroadcasts = 0;
+    let mut failures = 0;
+    loop {
+        tokio::time::sleep(REBROADCAST_INTERVAL).await;
+
+        // Checked before the status, so that an expired transaction gets a last status check.
+        // A failed check says nothing about the transaction, which may still land: it is
+        // retried, and the journal entry stays pending if the node never answers again.
+        let checked = match is_expired(rpc_client, &blockhash, nonce).await {
+            Ok(expired) => rpc_client
+                .get_signature_statuses(&[signature])
+                .await
+                .map(|statuses| (expired, statuses.value.into_iter().next().flatten()))
+                .map_err(eyre::Report::from),
+            Err(err) => Err(err),
+        };
+        let (expired, status) = match checked {
+            Ok(checked) => {
+                failures = 0;
+                checked
+            }
+            Err(err) if failures < MAX_STATUS_FAILURES => {
+                failures += 1;
+                progress.set_message(format!(
+                    "Waiting for {} confirmation, status check failed ({failures}/{MAX_STATUS_FAILURES}): {err:#}",
+                    commitment.commitment
+                ));
+                continue;
+            }
+            Err(err) => {
+                return Err(Error::Rpc(format!(
+                    "Lost track of transaction {signature}, it may still land: {err:#}. Check it \
+                     with `tx-status {signature}`"
+                ))
+                .into())
+            }
+        };
+        match status {
+            Some(status) => {
+                if let Some(err) = status.err {
+                    journal.set_status(&signature.to_string(), JournalStatus::Failed);
+                    return Err(Error::Program(explained(
+                        format!("Transaction failed: {}", err.to_string().red()),
+                        &err,
+                        &transaction.message,
+                        &[],
+                        program_id,
+                    ))
+                    .into());
+                }
+                if status.satisfies_commitment(commitment) {
+                    journal.set_status(&signature.to_string(), JournalStatus::Confirmed);
+                    return Ok(Some(signature));
+                }
+                // Landed, so it no longer needs rebroadcasting and cannot expire.
+                progress.set_message(format!(
+                    "Transaction landed in slot {}, waiting for {} confirmation...",
+                    status.slot, commitment.commitment
+                ));
+            }
+            None if expired => {
+                journal.set_status(&signature.to_string(), JournalStatus::Expired);
+                return Ok(None);
+            }
+            None => {
+                rebroadcasts += 1;
+                progress.set_message(format!(
+                    "Waiting for {} confirmation, rebroadcast {} times...",
+                    commitment.commitment, rebroadcasts
+                ));
+                // The transaction already passed preflight, a failed rebroadcast is just retried.
+                let _ = rpc_client
+                    .send_transaction_with_config(
+                        transaction,
+                        RpcSendTransactionConfig {
+                            skip_preflight: true,
+                            ..RpcSendTransactionConfig::default()
                         },
-                    ),
-                ..
-            }) = &*err.kind
-            {
-                println!("Simulation logs:\n\n{}\n", logs.join("\n").yellow());
+                    )
+                    .await;
             }
+        }
+    }
+}
+
+/// Whether a transaction using `blockhash` can no longer land: the blockhash is past its last
+/// valid block height or, with a durable nonce, the nonce has advanced.
+pub async fn is_expired(
+    rpc_client: &RpcClient,
+    blockhash: &Hash,
+    nonce: Option<Pubkey>,
+) -> eyre::Result<bool> {
+    match nonce {
+        Some(nonce) => Ok(get_nonce_data(rpc_client, &nonce).await?.blockhash() != *blockhash),
+        None => Ok(!rpc_client
+            .is_blockhash_valid(blockhash, CommitmentConfig::processed())
+            .await?),
+    }
+}
+
+/// The nonce account advanced by `message`, if it uses a durable nonce.
+fn durable_nonce(message: &VersionedMessage) -> Option<Pubkey> {
+    let keys = message.static_account_keys();
+    let ix = message.instructions().first()?;
+    if *keys.get(ix.program_id_index as usize)? != solana_system_interface::program::ID {
+        return None;
+    }
+    match bincode::deserialize(&ix.data).ok()? {
+        SystemInstruction::AdvanceNonceAccount => keys.get(*ix.accounts.first()? as usize).copied(),
+        _ => None,
+    }
+}
+
+/// A transaction rejected by preflight is a [`Error::Simulation`] with its logs, anything else is
+/// the node failing to take it.
+fn transaction_error(
+    err: ClientError,
+    message: &VersionedMessage,
+    program_id: &Pubkey,
+) -> eyre::Report {
+    let text = format!("Transaction failed: {}", err.to_string().red());
+    if let ClientErrorKind::RpcError(RpcError::RpcResponseError {
+        data:
+            RpcResponseErrorData::SendTransactionPreflightFailure(RpcSimulateTransactionResult {
+                err: simulation_err,
+                logs,
+                ..
+            }),
+        ..
+    }) = &*err.kind
+    {
+        let logs = logs.clone().unwrap_or_default();
+        let text = match simulation_err {
+            Some(simulation_err) => explained(
+                text,
+                &simulation_err.clone().into(),
+                message,
+                &logs,
+                program_id,
+            ),
+            None => text,
+        };
+        return Error::Simulation {
+            message: text,
+            logs,
+        }
+        .into();
+    }
+
+    Error::Rpc(text).into()
+}
+
+/// Appends to `text` what `err` means, when the failing program is one [`program_error`] knows.
+fn explained(
+    text: String,
+    err: &TransactionError,
+    message: &VersionedMessage,
+    logs: &[String],
+    program_id: &Pubkey,
+) -> String {
+    match program_error::explain(err, message, logs, program_id) {
+        Some(explanation) => format!("{}\n{}", text, explanation.yellow()),
+        None => text,
+    }
+}
+
+/// Times a proposal is rebuilt with the next index after another member took its index.
+pub const MAX_INDEX_RETRIES: usize = 3;
+
+/// Index of the next proposal of `multisig`, read again right before sending: another member may
+/// have created a proposal since `transaction_index` was shown on the review screen.
+pub async fn refresh_transaction_index(
+    rpc_client: &RpcClient,
+    multisig: &Pubkey,
+    transaction_index: u64,
+) -> eyre::Result<u64> {
+    let next_index = get_multisig(rpc_client, multisig).await?.transaction_index + 1;
+    if next_index != transaction_index {
+        info!(
+            "{}",
+            format!(
+                "Transaction index {} was taken in the meantime, using {}",
+                transaction_index, next_index
+            )
+            .yellow()
+        );
+    }
+    Ok(next_index)
+}
+
+/// Whether the proposal at `transaction_index` exists and was created by someone other than
+/// `creator`. Creating a proposal fails this way when another member takes its index first.
+pub async fn is_index_taken(
+    rpc_client: &RpcClient,
+    multisig: &Pubkey,
+    transaction_index: u64,
+    creator: &Pubkey,
+    program_id: &Pubkey,
+) -> bool {
+    let transaction_pda = get_transaction_pda(multisig, transaction_index, Some(program_id));
+    matches!(
+        get_transaction(rpc_client, &transaction_pda.0).await,
+        Ok(transaction) if transaction.creator != *creator
+    )
+}
+
+/// Blockhash for the message: the one given with `--blockhash`, else the value stored in the
+/// `--nonce` account, else the latest one.
+pub async fn get_blockhash(
+    rpc_client: &RpcClient,
+    blockhash: Option<Hash>,
+    nonce: Option<Pubkey>,
+) -> eyre::Result<Hash> {
+    match (blockhash, nonce) {
+        (Some(blockhash), _) => Ok(blockhash),
+        (None, Some(nonce)) => Ok(get_nonce_data(rpc_client, &nonce).await?.blockhash()),
+        (None, None) => Ok(rpc_client.get_latest_blockhash().await?),
+    }
+}
+
+/// Fetches and decodes a durable nonce account.
+pub async fn get_nonce_data(rpc_client: &RpcClient, nonce: &Pubkey) -> eyre::Result<NonceData> {
+    let account = nonblocking::get_account(rpc_client, nonce)
+        .await
+        .map_err(|err| Error::Rpc(format!("Failed to fetch nonce account {}: {}", nonce, err)))?;
+    nonblocking::data_from_account(&account).map_err(|err| {
+        Error::InvalidInput(format!(
+            "{} is not an initialized nonce account: {}",
+            nonce, err
+        ))
+        .into()
+    })
+}
+
+/// Prepends `advance_nonce_account` when a durable nonce is used. The runtime only accepts the
+/// nonce as blockhash if this is the first instruction of the message.
+pub fn with_nonce(
+    nonce: Option<Pubkey>,
+    nonce_authority: &Pubkey,
+    instructions: Vec<Instruction>,
+) -> Vec<Instruction> {
+    match nonce {
+        Some(nonce) => std::iter::once(advance_nonce_account(&nonce, nonce_authority))
+            .chain(instructions)
+            .collect(),
+        None => instructions,
+    }
+}
+
+/// Highest compute unit limit a transaction may request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Limit used when the transaction cannot be simulated, as with `--sign-only`.
+pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// Simulates `instructions`, which must request [`MAX_COMPUTE_UNIT_LIMIT`], and returns the units
+/// consumed plus `margin` percent, to be used as the transaction's compute unit limit.
+pub async fn estimate_compute_unit_limit(
+    rpc_client: &RpcClient,
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    margin: u32,
+    program_id: &Pubkey,
+) -> eyre::Result<u32> {
+    let message = Message::try_compile(payer, instructions, lookup_tables, Hash::default())?;
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::V0(message),
+    };
+
+    let result = rpc_client
+        .simulate_transaction_with_config(
+            &transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(rpc_client.commitment()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+
+    if let Some(err) = result.err {
+        let logs = result.logs.unwrap_or_default();
+        let message = explained(
+            format!(
+                "Simulation failed while estimating compute units: {}",
+                err.to_string().red()
+            ),
+            &err.into(),
+            &transaction.message,
+            &logs,
+            program_id,
+        );
+        return Err(Error::Simulation { message, logs }.into());
+    }
+
+    let units = result
+        .units_consumed
+        .ok_or_else(|| eyre!("The RPC node did not report the compute units consumed"))?;
+    let limit = units * (100 + u64::from(margin)) / 100;
+    Ok(limit.min(u64::from(MAX_COMPUTE_UNIT_LIMIT)) as u32)
+}
+
+/// Simulates `message` without signing or sending it and prints the program logs, compute units
+/// consumed and the resulting changes to the writable accounts.
+pub async fn simulate_transaction(
+    message: VersionedMessage,
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+) -> eyre::Result<()> {
+    let writable_accounts: Vec<_> = message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| message.is_maybe_writable(*index, None))
+        .map(|(_, key)| *key)
+        .collect();
+
+    let accounts_before = rpc_client.get_multiple_accounts(&writable_accounts).await?;
+
+    // Signature verification is skipped, so placeholder signatures are enough and no
+    // hardware wallet has to be touched.
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header().num_required_signatures as usize],
+        message,
+    };
+
+    let result = rpc_client
+        .simulate_transaction_with_config(
+            &transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(rpc_client.commitment()),
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: writable_accounts.iter().map(|k| k.to_string()).collect(),
+                }),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+
+    let accounts_after = result.accounts.unwrap_or_default();
+    let account_changes = writable_accounts
+        .iter()
+        .zip(accounts_before)
+        .zip(accounts_after)
+        .map(|((address, before), after)| AccountChange {
+            address: address.to_string(),
+            lamports_before: before.as_ref().map_or(0, |a| a.lamports),
+            lamports_after: after.as_ref().map_or(0, |a| a.lamports),
+            data_len_before: before.as_ref().map_or(0, |a| a.data.len()),
+            data_len_after: after
+                .as_ref()
+                .and_then(|a| a.data.decode())
+                .map_or(0, |data| data.len()),
+            owner_before: before.as_ref().map(|a| a.owner.to_string()),
+            owner_after: after.as_ref().map(|a| a.owner.clone()),
+        })
+        .collect();
+
+    let logs = result.logs.unwrap_or_default();
+    let simulation = SimulationOutput {
+        err: result.err.as_ref().map(|err| err.to_string()),
+        explanation: result.err.and_then(|err| {
+            program_error::explain(&err.into(), &transaction.message, &logs, program_id)
+        }),
+        units_consumed: result.units_consumed,
+        logs,
+        account_changes,
+    };
+    output::print(&simulation, print_simulation)?;
+
+    // The logs were printed with the simulation.
+    let message = match (simulation.err, simulation.explanation) {
+        (Some(err), Some(explanation)) => {
+            format!("Simulation failed: {}\n{}", err.red(), explanation.yellow())
+        }
+        (Some(err), None) => format!("Simulation failed: {}", err.red()),
+        (None, _) => return Ok(()),
+    };
+    Err(Error::Simulation {
+        message,
+        logs: Vec::new(),
+    }
+    .into())
+}
+
+fn print_simulation(simulation: &SimulationOutput) {
+    println!();
+    match &simulation.err {
+        Some(err) => println!("{} {}", "❌ Simulation failed:".red(), err),
+        None => println!("{}", "🔎 Simulation succeeded, nothing was sent.".green()),
+    }
+    if let Some(explanation) = &simulation.explanation {
+        println!("{}", explanation.yellow());
+    }
+    println!();
+    println!(
+        "Compute units consumed:   {}",
+        simulation
+            .units_consumed
+            .map(|units| units.to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    );
+    println!();
+    println!("Program logs:\n\n{}\n", simulation.logs.join("\n").yellow());
+    println!("Account changes:");
+    for change in &simulation.account_changes {
+        println!();
+        println!("  {}", change.address.bold());
+        let delta = change.lamports_after as i128 - change.lamports_before as i128;
+        println!(
+            "    lamports: {} → {} ({:+})",
+            change.lamports_before, change.lamports_after, delta
+        );
+        if change.data_len_before != change.data_len_after {
+            println!(
+                "    data:     {} → {} bytes",
+                change.data_len_before, change.data_len_after
+            );
+        }
+        if change.owner_before != change.owner_after {
+            println!(
+                "    owner:    {} → {}",
+                change.owner_before.as_deref().unwrap_or("none"),
+                change.owner_after.as_deref().unwrap_or("none")
+            );
+        }
+    }
+}
+
+/// Prints the message and the signatures made on this machine for `--sign-only`, in the form
+/// `broadcast` expects them.
+pub fn print_sign_only(transaction: &VersionedTransaction) -> eyre::Result<()> {
+    let signer_keys = &transaction.message.static_account_keys()
+        [..transaction.message.header().num_required_signatures as usize];
+
+    let mut signers = Vec::new();
+    let mut absent = Vec::new();
+    for (key, signature) in signer_keys.iter().zip(&transaction.signatures) {
+        if *signature == Signature::default() {
+            absent.push(key.to_string());
+        } else {
+            signers.push(format!("{key}={signature}"));
+        }
+    }
+
+    let sign_only = SignOnlyOutput {
+        blockhash: transaction.message.recent_blockhash().to_string(),
+        message: bs58::encode(transaction.message.serialize()).into_string(),
+        signers,
+        absent,
+    };
 
-            Err(eyre!("Transaction failed: {}", err.to_string().red()))
+    output::print(&sign_only, |sign_only| {
+        println!();
+        println!("Blockhash: {}", sign_only.blockhash);
+        println!("Message:   {}", sign_only.message);
+        println!();
+        println!("Signers (Pubkey=Signature):");
+        for signer in &sign_only.signers {
+            println!("  {}", signer.green());
+        }
+        if !sign_only.absent.is_empty() {
+            println!();
+            println!("Absent Signers (Pubkey):");
+            for key in &sign_only.absent {
+                println!("  {}", key.yellow());
+            }
         }
+        println!();
+        println!(
+            "Once every signature is collected, submit with:\n  broadcast --message <MESSAGE> --signer <PUBKEY=SIGNATURE> ..."
+        );
+    })
+}
+
+/// Data of the account at `address`. A missing account is an [`Error::AccountNotFound`] naming
+/// it as `what`, e.g. "Proposal".
+pub async fn get_account_data(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+    what: &str,
+) -> eyre::Result<Vec<u8>> {
+    let account = rpc_client
+        .get_account_with_commitment(address, rpc_client.commitment())
+        .await?
+        .value
+        .ok_or_else(|| Error::AccountNotFound(format!("{} {} does not exist", what, address)))?;
+    Ok(account.data)
+}
+
+/// Loads the address lookup tables a vault transaction message refers to.
+pub async fn get_necessary_alt(
+    rpc_client: &RpcClient,
+    message: &VaultTransactionMessage,
+) -> eyre::Result<Vec<AddressLookupTableAccount>> {
+    let mut address_lookup_table_accounts: Vec<AddressLookupTableAccount> = Vec::new();
+    let address_lookup_table_keys = message
+        .address_table_lookups
+        .iter()
+        .map(|lookup| lookup.account_key)
+        .collect::<Vec<_>>();
+    for key in address_lookup_table_keys {
+        let account_data = get_account_data(rpc_client, &key, "Address lookup table").await?;
+        let lookup_table =
+            solana_address_lookup_table_interface::state::AddressLookupTable::deserialize(
+                &account_data,
+            )
+            .map_err(|err| {
+                Error::Deserialization(format!("Invalid address lookup table {}: {}", key, err))
+            })?;
+
+        let address_lookup_table_account = AddressLookupTableAccount {
+            addresses: lookup_table
+                .addresses
+                .iter()
+                .map(|pk| solana_message::Address::new_from_array(pk.to_bytes()))
+                .collect(),
+
+            key,
+        };
+
+        address_lookup_table_accounts.push(address_lookup_table_account);
+    }
+
+    Ok(address_lookup_table_accounts)
+}
+
+/// Current Unix timestamp.
+pub fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// `Active`, `Expired`, `Approved` or `Executed`. An active proposal past its deadline can no
+/// longer be voted on, so it is reported as expired. `now` is the cluster time from
+/// [`FortisClient::cluster_time`](crate::client::FortisClient::cluster_time), which the program
+/// checks the deadline against, rather than the local clock.
+pub fn proposal_status(proposal: &Proposal, now: i64) -> &'static str {
+    match proposal.status {
+        0 if now > proposal.deadline => "Expired",
+        0 => "Active",
+        1 => "Approved",
+        _ => "Executed",
+    }
+}
+
+/// `timestamp` (unix seconds) as a date and time in the local time zone, e.g.
+/// `2025-10-12 10:53:20 +02:00`.
+pub fn format_timestamp(timestamp: i64) -> String {
+    match Local.timestamp_opt(timestamp, 0).single() {
+        Some(time) => time.format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+/// `deadline` as a local date and time with the time left at `now`, e.g.
+/// `2025-10-12 10:53:20 +02:00 (2d 4h 10m left)`.
+pub fn format_deadline(deadline: i64, now: i64) -> String {
+    format!(
+        "{} ({})",
+        format_timestamp(deadline),
+        format_countdown(deadline, now)
+    )
+}
+
+/// Time left at `now` until `deadline` (both unix seconds, `now` normally the cluster clock),
+/// e.g. `2d 4h 10m left`, or how long ago it passed.
+pub fn format_countdown(deadline: i64, now: i64) -> String {
+    let remaining = deadline - now;
+    let seconds = remaining.unsigned_abs();
+    let (days, hours, minutes) = (
+        seconds / 86_400,
+        seconds % 86_400 / 3_600,
+        seconds % 3_600 / 60,
+    );
+    let duration = if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m {}s", seconds % 60)
+    };
+    if remaining >= 0 {
+        format!("{duration} left")
+    } else {
+        format!("passed {duration} ago")
     }
 }
@@ -0,0 +1,106 @@
+//! The library sends like the CLI: [`FortisClient`] goes through the same send path, so its
+//! failures are typed and explained, and a node that stops answering for a moment does not
+//! fail a transaction that lands. Account reads tell a missing account from an unreachable
+//! node or a malformed account.
+
+mod common;
+
+use common::*;
+use fortis_multisig_cli::error::{exit_code, Error};
+use fortis_multisig_cli::FortisClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::signature::read_keypair_file;
+
+fn client(sender: &FixtureSender) -> FortisClient {
+    FortisClient::new(
+        sender.rpc_client(RpcClientConfig::with_commitment(
+            CommitmentConfig::confirmed(),
+        )),
+        fortis_sdk::pda::FORTIS_PROGRAM_ID,
+    )
+}
+
+#[tokio::test]
+async fn failed_transaction_is_a_typed_program_error() {
+    let sender =
+        FixtureSender::load(&["clock", "multisig", "proposal"]).with_mock("instruction_error");
+    let client = client(&sender);
+    let member = read_keypair_file(member_keypair()).unwrap();
+
+    let err = client.approve(&member, &MULTISIG, 3).await.unwrap_err();
+    match err.downcast_ref::<Error>() {
+        Some(Error::Program(message)) => {
+            assert!(message.contains("Transaction failed"), "{message}")
+        }
+        _ => panic!("expected Error::Program, got {err:?}"),
+    }
+    assert_eq!(sender.transactions().len(), 1);
+}
+
+#[tokio::test]
+async fn rejected_transaction_carries_its_logs() {
+    let logs = [
+        "Program log: Instruction: ProposalApprove",
+        "Program log: Error: already approved",
+    ];
+    let sender = FixtureSender::load(&["clock", "multisig", "proposal"]).rejecting(&logs);
+    let client = client(&sender);
+    let member = read_keypair_file(member_keypair()).unwrap();
+
+    let err = client.approve(&member, &MULTISIG, 3).await.unwrap_err();
+    match err.downcast_ref::<Error>() {
+        Some(Error::Simulation { logs: returned, .. }) => assert_eq!(returned, &logs),
+        _ => panic!("expected Error::Simulation, got {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn confirmation_survives_failed_status_checks() {
+    let sender = FixtureSender::load(&["clock", "multisig", "proposal"])
+        .failing("isBlockhashValid", 1)
+        .failing("getSignatureStatuses", 1);
+    let client = client(&sender);
+    let member = read_keypair_file(member_keypair()).unwrap();
+
+    let sent = client.approve(&member, &MULTISIG, 3).await.unwrap();
+    assert_eq!(sender.transactions()[0].signatures[0], sent.signature);
+}
+
+#[tokio::test]
+async fn missing_proposal_is_account_not_found() {
+    let client = client(&FixtureSender::load(&["multisig"]));
+
+    let err = client.proposal(&MULTISIG, 3).await.err().unwrap();
+    assert!(
+        matches!(err.downcast_ref(), Some(Error::AccountNotFound(_))),
+        "{err:?}"
+    );
+    assert_eq!(
+        fortis_multisig_cli::error::exit_code(&err),
+        exit_code::ACCOUNT_NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn unreachable_node_is_not_a_missing_account() {
+    let client = client(&FixtureSender::load(&["proposal"]).failing("getAccountInfo", 1));
+
+    let err = client.proposal(&MULTISIG, 3).await.err().unwrap();
+    assert_eq!(fortis_multisig_cli::error::exit_code(&err), exit_code::RPC);
+}
+
+#[tokio::test]
+async fn truncated_proposal_is_invalid() {
+    for len in [10, 70] {
+        let client = client(&FixtureSender::load(&[]).with_account(PROPOSAL, &[1; 126][..len]));
+
+        let err = client.proposal(&MULTISIG, 3).await.err().unwrap();
+        match err.downcast_ref::<Error>() {
+            Some(Error::Deserialization(message)) => {
+                assert!(message.contains("is too short"), "{message}")
+            }
+            _ => panic!("expected Error::Deserialization, got {err:?}"),
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Offline RPC for the command tests: accounts come from the recorded fixtures in
+//! `tests/fixtures/accounts` and every transaction the commands send or simulate is kept for
+//! inspection.
+
+// Each test crate uses a different part of these helpers.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::Parser;
+use fortis_multisig_cli::command::Cli;
+use fortis_multisig_cli::config::Config;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_client::client_error::{ClientError, Result as ClientResult};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::{RpcError, RpcRequest, RpcResponseErrorData};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_rpc_client::mock_sender::MockSender;
+use solana_sdk::{pubkey, pubkey::Pubkey, transaction::VersionedTransaction};
+
+// Addresses in the recorded accounts: a 2-of-2 multisig at transaction index 3, whose
+// proposal #3 is approved and transfers tokens to a recipient held in an address lookup table.
+pub const MULTISIG: Pubkey = pubkey!("6AGjJrL6PdP4qqvrPmEsPgFqKwS5dR34yGBM5kufNnem");
+pub const VAULT: Pubkey = pubkey!("5m3a2aZEg2m152YV3WCfMt4mh2mWcCwHgSRmAFYaZFME");
+pub const MEMBER: Pubkey = pubkey!("AKnL4NNf3DGWZJS6cPknBuEGnVsV4A4m5tgebLHaRSZ9");
+pub const OTHER_MEMBER: Pubkey = pubkey!("9hSR6S7WPtxmTojgo6GG3k4yDPecgJY292j7xrsUGWBu");
+pub const MINT: Pubkey = pubkey!("EdmxWPmx2WH6WgFfTdu9xfkYf3k1g5wD1zccTVySEEh1");
+pub const RECIPIENT: Pubkey = pubkey!("8SFqwqnq4whPhs8icwHA2hQg3hUoN1qrCLK1SBx3WKwe");
+pub const LOOKUP_TABLE: Pubkey = pubkey!("AKkzLhjhyFtM9j7WAhbaqYpFe49cXeJBg2kzLRC2PnNa");
+/// Token accounts of the vault and the recipient for `MINT`.
+pub const VAULT_TOKEN_ACCOUNT: Pubkey = pubkey!("3fvLskRNH4Y7dsHmeDPQ57Ao59AJHTjTeKkXfh61BLCS");
+pub const RECIPIENT_TOKEN_ACCOUNT: Pubkey = pubkey!("7JDarA5eLQn1XgrAyneHANX4mNpCes9KF5BeJLRbDQyo");
+/// Unix time of the clock sysvar in `clock.json`, between the creation and the deadline of
+/// proposal #3.
+pub const CLUSTER_TIME: i64 = 1_760_100_000;
+/// Proposal and transaction accounts of proposal #3.
+pub const PROPOSAL: Pubkey = pubkey!("tZjyTHGLYG7SvabSMEtuNfYp6jzVtMHLwcCTcKvaWNA");
+pub const TRANSACTION: Pubkey = pubkey!("Hh5nhyGreUKxo77BEsL7hB5WZ9ejHdEqQ1JsWoT2opsN");
+
+/// An account as written by `solana account --output json`.
+#[derive(Deserialize)]
+struct AccountFixture {
+    pubkey: String,
+    account: Value,
+}
+
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Path of the keypair that is a member of the fixture multisig.
+pub fn member_keypair() -> String {
+    fixtures_dir()
+        .join("member.json")
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Answers account reads from fixtures and records transactions. Every other request gets the
+/// canned response of [`MockSender`]: a fresh blockhash, a successful simulation, and so on.
+#[derive(Clone)]
+pub struct FixtureSender {
+    accounts: Arc<HashMap<Pubkey, Value>>,
+    transactions: Arc<Mutex<Vec<VersionedTransaction>>>,
+    /// Requests still to fail, by method
+    failures: Arc<Mutex<HashMap<String, usize>>>,
+    /// Program logs of the preflight failure every sent transaction is rejected with
+    rejection: Option<Arc<Vec<String>>>,
+    mock: Arc<MockSender>,
+}
+
+impl FixtureSender {
+    /// Serves the accounts recorded in `tests/fixtures/accounts/<name>.json`.
+    pub fn load(names: &[&str]) -> Self {
+        let accounts = names
+            .iter()
+            .map(|name| {
+                let path = fixtures_dir().join(format!("accounts/{name}.json"));
+                let fixture: AccountFixture =
+                    serde_json::from_str(&std::fs::read_to_string(&path).unwrap())
+                        .unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+                (fixture.pubkey.parse().unwrap(), fixture.account)
+            })
+            .collect();
+        Self {
+            accounts: Arc::new(accounts),
+            transactions: Arc::default(),
+            failures: Arc::default(),
+            rejection: None,
+            mock: Arc::new(MockSender::new("succeeds")),
+        }
+    }
+
+    /// Also serves `data` at `address`, e.g. a truncated copy of a recorded account.
+    pub fn with_account(mut self, address: Pubkey, data: &[u8]) -> Self {
+        Arc::make_mut(&mut self.accounts).insert(
+            address,
+            json!({
+                "data": [STANDARD.encode(data), "base64"],
+                "executable": false,
+                "lamports": 1_000_000,
+                "owner": fortis_sdk::pda::FORTIS_PROGRAM_ID.to_string(),
+                "rentEpoch": u64::MAX,
+                "space": data.len(),
+            }),
+        );
+        self
+    }
+
+    /// Answers the requests that are not about accounts like the [`MockSender`] for `url`, e.g.
+    /// `instruction_error` to have every transaction fail once it lands.
+    pub fn with_mock(mut self, url: &str) -> Self {
+        self.mock = Arc::new(MockSender::new(url));
+        self
+    }
+
+    /// Fails the next `times` requests of `method`, like a node that is briefly unreachable.
+    pub fn failing(self, method: &str, times: usize) -> Self {
+        self.failures
+            .lock()
+            .unwrap()
+            .insert(method.to_string(), times);
+        self
+    }
+
+    /// Rejects every sent transaction in preflight, as failing its first instruction with
+    /// custom error 1 after printing `logs`.
+    pub fn rejecting(mut self, logs: &[&str]) -> Self {
+        self.rejection = Some(Arc::new(logs.iter().map(|log| log.to_string()).collect()));
+        self
+    }
+
+    pub fn rpc_client(&self, config: RpcClientConfig) -> RpcClient {
+        RpcClient::new_sender(self.clone(), config)
+    }
+
+    /// Transactions sent or simulated so far, oldest first.
+    pub fn transactions(&self) -> Vec<VersionedTransaction> {
+        self.transactions.lock().unwrap().clone()
+    }
+
+    fn account(&self, pubkey: &Value) -> Value {
+        let pubkey: Pubkey = pubkey.as_str().unwrap().parse().unwrap();
+        self.accounts.get(&pubkey).cloned().unwrap_or(Value::Null)
+    }
+
+    fn record(&self, params: &Value) -> VersionedTransaction {
+        let data = STANDARD.decode(params[0].as_str().unwrap()).unwrap();
+        let transaction: VersionedTransaction = bincode::deserialize(&data).unwrap();
+        self.transactions.lock().unwrap().push(transaction.clone());
+        transaction
+    }
+}
+
+#[async_trait]
+impl RpcSender for FixtureSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        // Matched by name so requests relayed by `serve` as `RpcRequest::Custom` are answered the
+        // same way.
+        let method = request.to_string();
+        if let Some(times) = self.failures.lock().unwrap().get_mut(&method) {
+            if *times > 0 {
+                *times -= 1;
+                return Err(std::io::Error::other(format!("{method} unavailable")).into());
+            }
+        }
+        let value = match method.as_str() {
+            "getAccountInfo" => self.account(&params[0]),
+            "getMultipleAccounts" => params[0]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|pubkey| self.account(pubkey))
+                .collect(),
+            // The recorded blockhashes never expire.
+            "isBlockhashValid" => json!(true),
+            "sendTransaction" => {
+                let signature = self.record(&params).signatures[0];
+                return match &self.rejection {
+                    Some(logs) => Err(preflight_failure(logs)),
+                    None => Ok(json!(signature.to_string())),
+                };
+            }
+            "simulateTransaction" => {
+                self.record(&params);
+                return self.mock.send(request, params).await;
+            }
+            _ => return self.mock.send(request, params).await,
+        };
+        Ok(json!({ "context": { "slot": 1 }, "value": value }))
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "fixtures".to_string()
+    }
+}
+
+fn preflight_failure(logs: &[String]) -> ClientError {
+    let result: RpcSimulateTransactionResult = serde_json::from_value(json!({
+        "err": { "InstructionError": [0, { "Custom": 1 }] },
+        "logs": logs,
+    }))
+    .unwrap();
+    RpcError::RpcResponseError {
+        code: -32002,
+        message: "Transaction simulation failed: Error processing Instruction 0: custom program \
+                  error: 0x1"
+            .to_string(),
+        data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+    }
+    .into()
+}
+
+/// Runs a CLI command the way the binary does, without prompting, with every RPC request
+/// answered by `sender`.
+pub async fn fortis(sender: &FixtureSender, args: &[&str]) -> eyre::Result<()> {
+    let cli = parse(&[&["--url", &sender.url()], args].concat());
+    let sender = sender.clone();
+    let config = Config::load(cli.global, false)?.with_rpc_client(move |_, commitment| {
+        sender.rpc_client(RpcClientConfig::with_commitment(commitment))
+    });
+    cli.command.execute(&config).await
+}
+
+/// Runs a CLI command on a machine without a cluster: no RPC URL is configured, and the RPC
+/// client connects nowhere.
+pub async fn fortis_offline(args: &[&str]) -> eyre::Result<()> {
+    let cli = parse(args);
+    let config = Config::load(cli.global, false)?;
+    cli.command.execute(&config).await
+}
+
+/// Parses `args` with the config file, journal and Solana CLI config of a fresh scratch
+/// directory, so the developer's own are never read.
+fn parse(args: &[&str]) -> Cli {
+    static DIRS: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "fortis-fixtures-{}-{}",
+        std::process::id(),
+        DIRS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config_path = dir.join("config.toml");
+    let solana_config_path = dir.join("solana-cli.yml");
+    Cli::try_parse_from(
+        [
+            "fortis-multisig-cli",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--solana-config",
+            solana_config_path.to_str().unwrap(),
+            "--yes",
+        ]
+        .iter()
+        .chain(args),
+    )
+    .unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Serves `sender` as a JSON-RPC endpoint on a local port, for running the binary itself against
+/// the fixtures. Returns the URL.
+pub fn serve(sender: FixtureSender) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let sender = sender.clone();
+            std::thread::spawn(move || serve_connection(&sender, stream));
+        }
+    });
+    url
+}
+
+/// Answers the HTTP requests of one keep-alive connection until the client closes it.
+fn serve_connection(sender: &FixtureSender, mut stream: TcpStream) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    loop {
+        // Request line and headers, up to the blank line.
+        let mut content_length = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let request: Value = serde_json::from_slice(&body).unwrap();
+        // The CLI only calls a handful of methods, so leaking their names is fine.
+        let method = Box::leak(
+            request["method"]
+                .as_str()
+                .unwrap()
+                .to_string()
+                .into_boxed_str(),
+        );
+        let response = match runtime
+            .block_on(sender.send(RpcRequest::Custom { method }, request["params"].clone()))
+        {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            Err(err) => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32000, "message": err.to_string() },
+            }),
+        }
+        .to_string();
+
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            response.len(),
+            response
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,346 @@
+//! Full treasury lifecycle against `solana-test-validator` running the Fortis program:
+//! `multisig-create` → `initiate-native-transfer` → `proposal-approve` by every member →
+//! `proposal-execute` → `proposal-accounts-close`, driven through the CLI command structs.
+//!
+//! Needs `solana-test-validator` on the PATH and the program binary at
+//! `tests/fixtures/fortis.so` (or the path in `FORTIS_PROGRAM_SO`), which is not committed, so
+//! the test only runs with `--ignored`.
+
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command as Process, Stdio};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use fortis_multisig_cli::command::Cli;
+use fortis_multisig_cli::config::Config;
+use fortis_multisig_cli::journal;
+use fortis_multisig_cli::FortisClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{write_keypair_file, Keypair, Signer},
+};
+
+const VALIDATOR_STARTUP: Duration = Duration::from_secs(60);
+
+/// A `solana-test-validator` with the Fortis program deployed, killed on drop.
+struct TestValidator {
+    process: Child,
+    rpc_url: String,
+}
+
+impl TestValidator {
+    /// The program binary to deploy, or why the validator cannot run here.
+    fn program() -> Result<PathBuf, String> {
+        let program = std::env::var_os("FORTIS_PROGRAM_SO")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fortis.so")
+            });
+        if !program.exists() {
+            return Err(format!(
+                "Fortis program binary not found at {}",
+                program.display()
+            ));
+        }
+        match Process::new("solana-test-validator")
+            .arg("--version")
+            .output()
+        {
+            Ok(output) if output.status.success() => Ok(program),
+            _ => Err("solana-test-validator is not on the PATH".to_string()),
+        }
+    }
+
+    async fn start(dir: &Path, program: &Path) -> Self {
+        let rpc_port = free_port();
+        let process = Process::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(dir.join("ledger"))
+            .arg("--rpc-port")
+            .arg(rpc_port.to_string())
+            .arg("--faucet-port")
+            .arg(free_port().to_string())
+            .arg("--bpf-program")
+            .arg(fortis_sdk::pda::FORTIS_PROGRAM_ID.to_string())
+            .arg(program)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start solana-test-validator");
+
+        let validator = Self {
+            process,
+            rpc_url: format!("http://127.0.0.1:{rpc_port}"),
+        };
+        let rpc_client = validator.rpc_client();
+        let started = Instant::now();
+        while rpc_client.get_health().await.is_err() {
+            assert!(
+                started.elapsed() < VALIDATOR_STARTUP,
+                "solana-test-validator did not start"
+            );
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        validator
+    }
+
+    fn rpc_client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.rpc_url.clone(), CommitmentConfig::confirmed())
+    }
+}
+
+impl Drop for TestValidator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Scratch directory holding the config file, journal, keypairs and ledger of one test.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("fortis-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn keypair_file(dir: &Path, name: &str) -> (Keypair, String) {
+    let keypair = Keypair::new();
+    let path = dir.join(format!("{name}.json"));
+    write_keypair_file(&keypair, &path).unwrap();
+    (keypair, path.to_string_lossy().into_owned())
+}
+
+async fn airdrop(rpc_client: &RpcClient, to: &Pubkey, lamports: u64) {
+    let balance = rpc_client.get_balance(to).await.unwrap();
+    rpc_client.request_airdrop(to, lamports).await.unwrap();
+    while rpc_client.get_balance(to).await.unwrap() < balance + lamports {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// Runs a CLI command the way the binary does, without prompting. The config file, journal and
+/// Solana CLI config all live in `dir`, so the developer's own are never read.
+async fn fortis(dir: &Path, rpc_url: &str, args: &[&str]) {
+    let config_path = dir.join("config.toml");
+    let solana_config_path = dir.join("solana-cli.yml");
+    let cli = Cli::try_parse_from(
+        [
+            "fortis-multisig-cli",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--solana-config",
+            solana_config_path.to_str().unwrap(),
+            "--url",
+            rpc_url,
+            "--yes",
+        ]
+        .iter()
+        .chain(args),
+    )
+    .unwrap_or_else(|err| panic!("{err}"));
+    let config = Config::load(cli.global, false).unwrap();
+    if let Err(err) = cli.command.execute(&config).await {
+        panic!("{} failed: {:?}", args[0], err);
+    }
+}
+
+/// The multisig created by the last `multisig-create`, read from the journal.
+fn created_multisig(dir: &Path) -> Pubkey {
+    journal::load(&dir.join(journal::JOURNAL_FILE))
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|entry| entry.command == "multisig-create")
+        .and_then(|entry| entry.multisig.as_deref())
+        .expect("multisig-create was not journaled")
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs solana-test-validator and the Fortis program binary at tests/fixtures/fortis.so"]
+async fn native_transfer_lifecycle() {
+    let program = TestValidator::program().unwrap_or_else(|reason| panic!("{reason}"));
+    let dir = test_dir("lifecycle");
+    let validator = TestValidator::start(&dir, &program).await;
+    let rpc_url = validator.rpc_url.as_str();
+    let rpc_client = validator.rpc_client();
+    let client = FortisClient::new(validator.rpc_client(), fortis_sdk::pda::FORTIS_PROGRAM_ID);
+
+    let (alice, alice_path) = keypair_file(&dir, "alice");
+    let (bob, bob_path) = keypair_file(&dir, "bob");
+    let recipient = Pubkey::new_unique();
+    airdrop(&rpc_client, &alice.pubkey(), 10 * LAMPORTS_PER_SOL).await;
+    airdrop(&rpc_client, &bob.pubkey(), 10 * LAMPORTS_PER_SOL).await;
+
+    let alice_key = alice.pubkey().to_string();
+    let bob_key = bob.pubkey().to_string();
+
+    // A 2-of-2 multisig whose closed proposal accounts refund Alice.
+    fortis(
+        &dir,
+        rpc_url,
+        &[
+            "multisig-create",
+            "--keypair",
+            &alice_path,
+            "--members",
+            &alice_key,
+            "--members",
+            &bob_key,
+            "--threshold",
+            "2",
+            "--rent-collector",
+            &alice_key,
+            "--priority-fee",
+            "0",
+        ],
+    )
+    .await;
+    let multisig = created_multisig(&dir);
+    let multisig_key = multisig.to_string();
+
+    let multisig_data = client.multisig(&multisig).await.unwrap();
+    assert_eq!(multisig_data.threshold, 2);
+    assert_eq!(multisig_data.members.len(), 2);
+    assert!(multisig_data.members.contains(&alice.pubkey()));
+    assert!(multisig_data.members.contains(&bob.pubkey()));
+    assert_eq!(multisig_data.rent_collector, alice.pubkey());
+    assert_eq!(multisig_data.transaction_index, 0);
+
+    let vault = client.vault(&multisig);
+    airdrop(&rpc_client, &vault, 2 * LAMPORTS_PER_SOL).await;
+
+    let deadline = (fortis_multisig_cli::utils::now() + 3_600).to_string();
+    let amount = LAMPORTS_PER_SOL.to_string();
+    fortis(
+        &dir,
+        rpc_url,
+        &[
+            "initiate-native-transfer",
+            "--keypair",
+            &alice_path,
+            "--multisig-pubkey",
+            &multisig_key,
+            "--recipient",
+            &recipient.to_string(),
+            "--token-amount-u64",
+            &amount,
+            "--voting-deadline",
+            &deadline,
+            "--priority-fee",
+            "0",
+        ],
+    )
+    .await;
+
+    assert_eq!(
+        client.multisig(&multisig).await.unwrap().transaction_index,
+        1
+    );
+    let proposal = client.proposal(&multisig, 1).await.unwrap();
+    assert_eq!(proposal.transaction_index, 1);
+    assert!(proposal.approved.is_empty());
+    assert_eq!(
+        client
+            .vault_transaction(&multisig, 1)
+            .await
+            .unwrap()
+            .creator,
+        alice.pubkey()
+    );
+
+    for keypair in [&alice_path, &bob_path] {
+        fortis(
+            &dir,
+            rpc_url,
+            &[
+                "proposal-approve",
+                "--keypair",
+                keypair,
+                "--multisig-pubkey",
+                &multisig_key,
+                "--transaction-index",
+                "1",
+                "--priority-fee",
+                "0",
+            ],
+        )
+        .await;
+    }
+
+    let proposal = client.proposal(&multisig, 1).await.unwrap();
+    assert_eq!(proposal.approved.len(), 2);
+    assert!(proposal.approved.contains(&alice.pubkey()));
+    assert!(proposal.approved.contains(&bob.pubkey()));
+
+    let vault_balance = rpc_client.get_balance(&vault).await.unwrap();
+    fortis(
+        &dir,
+        rpc_url,
+        &[
+            "proposal-execute",
+            "--keypair",
+            &bob_path,
+            "--multisig-pubkey",
+            &multisig_key,
+            "--transaction-index",
+            "1",
+            "--priority-fee",
+            "0",
+        ],
+    )
+    .await;
+
+    assert_eq!(
+        rpc_client.get_balance(&recipient).await.unwrap(),
+        LAMPORTS_PER_SOL
+    );
+    assert_eq!(
+        rpc_client.get_balance(&vault).await.unwrap(),
+        vault_balance - LAMPORTS_PER_SOL
+    );
+
+    let alice_balance = rpc_client.get_balance(&alice.pubkey()).await.unwrap();
+    fortis(
+        &dir,
+        rpc_url,
+        &[
+            "proposal-accounts-close",
+            "--keypair",
+            &alice_path,
+            "--multisig-pubkey",
+            &multisig_key,
+            "--transaction-index",
+            "1",
+            "--rent-collector",
+            &alice_key,
+            "--priority-fee",
+            "0",
+        ],
+    )
+    .await;
+
+    assert!(client.proposal(&multisig, 1).await.is_err());
+    assert!(client.vault_transaction(&multisig, 1).await.is_err());
+    // The reclaimed rent outweighs the fee Alice paid to close.
+    assert!(rpc_client.get_balance(&alice.pubkey()).await.unwrap() > alice_balance);
+
+    drop(validator);
+    let _ = std::fs::remove_dir_all(&dir);
+}
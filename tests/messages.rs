@@ -0,0 +1,404 @@
+//! Transactions built by the proposal commands, checked offline against recorded accounts: a
+//! 2-of-2 multisig at transaction index 3, whose proposal #3 transfers tokens to a recipient
+//! held in an address lookup table. Every command runs with `--dry-run`, so the transaction
+//! ends up simulated and recorded by the fixture RPC.
+
+mod common;
+
+use borsh::BorshDeserialize;
+use common::*;
+use fortis_multisig_cli::decode::{decode_vault_message, DecodedInstruction};
+use fortis_multisig_cli::utils;
+use fortis_sdk::pda::{get_proposal_pda, get_transaction_pda, FORTIS_PROGRAM_ID};
+use fortis_sdk::state::{ProposalCreateArgs, VaultTransactionMessage};
+use solana_message::{AddressLookupTableAccount, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+
+const VOTING_DEADLINE: &str = "1760259200";
+
+/// A Fortis instruction of a sent transaction, with its accounts resolved.
+struct SentInstruction {
+    accounts: Vec<(Pubkey, bool, bool)>,
+    data: Vec<u8>,
+}
+
+/// Runs a command with `--dry-run` as the fixture member, with a fixed compute budget so
+/// nothing else is simulated first.
+async fn dry_run(sender: &FixtureSender, args: &[&str]) {
+    let member = member_keypair();
+    let mut args = args.to_vec();
+    args.extend([
+        "--keypair",
+        &member,
+        "--dry-run",
+        "--compute-unit-limit",
+        "200000",
+        "--priority-fee",
+        "0",
+    ]);
+    if let Err(err) = fortis(sender, &args).await {
+        panic!("{} failed: {:?}", args[0], err);
+    }
+}
+
+/// The only Fortis instruction of the last transaction `sender` recorded. Accounts are
+/// `(address, signer, writable)`, with the lookup table addresses loaded from `lookup_tables`.
+fn fortis_instruction(
+    sender: &FixtureSender,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> SentInstruction {
+    let transaction = sender
+        .transactions()
+        .pop()
+        .expect("no transaction was sent");
+    let VersionedMessage::V0(message) = &transaction.message else {
+        panic!("expected a v0 message");
+    };
+
+    let mut keys = message.account_keys.clone();
+    let loaded = |writable: bool| {
+        message
+            .address_table_lookups
+            .iter()
+            .flat_map(move |lookup| {
+                let table = lookup_tables
+                    .iter()
+                    .find(|table| table.key == lookup.account_key)
+                    .expect("unknown lookup table");
+                let indexes = if writable {
+                    &lookup.writable_indexes
+                } else {
+                    &lookup.readonly_indexes
+                };
+                indexes.iter().map(|index| table.addresses[*index as usize])
+            })
+    };
+    keys.extend(loaded(true));
+    keys.extend(loaded(false));
+
+    let versioned = VersionedMessage::V0(message.clone());
+    let mut fortis = message
+        .instructions
+        .iter()
+        .filter(|ix| keys[ix.program_id_index as usize] == FORTIS_PROGRAM_ID);
+    let ix = fortis.next().expect("no Fortis instruction");
+    assert!(fortis.next().is_none(), "more than one Fortis instruction");
+
+    SentInstruction {
+        accounts: ix
+            .accounts
+            .iter()
+            .map(|index| {
+                let index = *index as usize;
+                (
+                    keys[index],
+                    versioned.is_signer(index),
+                    versioned.is_maybe_writable(index, None),
+                )
+            })
+            .collect(),
+        data: ix.data.clone(),
+    }
+}
+
+/// The vault message proposed by a `ProposalCreate` instruction, decoded.
+fn proposed_message(ix: &SentInstruction) -> (ProposalCreateArgs, Vec<DecodedInstruction>) {
+    assert_eq!(ix.data[0], 1, "not a ProposalCreate instruction");
+    let args = ProposalCreateArgs::try_from_slice(&ix.data[1..]).unwrap();
+    let message = VaultTransactionMessage::try_from_slice(&args.transaction_message).unwrap();
+    let instructions = decode_vault_message(&message, &[]);
+    (args, instructions)
+}
+
+fn accounts(instruction: &DecodedInstruction) -> Vec<(String, bool, bool)> {
+    instruction
+        .accounts
+        .iter()
+        .map(|account| (account.address.clone(), account.signer, account.writable))
+        .collect()
+}
+
+/// Accounts of a `ProposalCreate` for proposal #4, the next one of the fixture multisig.
+fn assert_creates_next_proposal(ix: &SentInstruction) {
+    assert_eq!(
+        ix.accounts,
+        vec![
+            (MULTISIG, false, true),
+            (get_transaction_pda(&MULTISIG, 4, None).0, false, true),
+            (MEMBER, true, true),
+            (get_proposal_pda(&MULTISIG, 4, None).0, false, true),
+            (solana_sdk_ids::system_program::ID, false, false),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn initiate_transfer_proposes_a_vault_token_transfer() {
+    let sender = FixtureSender::load(&["clock", "multisig"]);
+    let (mint, recipient) = (MINT.to_string(), RECIPIENT.to_string());
+    dry_run(
+        &sender,
+        &[
+            "initiate-transfer",
+            "--multisig-pubkey",
+            &MULTISIG.to_string(),
+            "--token-mint-address",
+            &mint,
+            "--recipient",
+            &recipient,
+            "--token-amount-u64",
+            "250000000",
+            "--voting-deadline",
+            VOTING_DEADLINE,
+        ],
+    )
+    .await;
+
+    let ix = fortis_instruction(&sender, &[]);
+    assert_creates_next_proposal(&ix);
+
+    let (args, instructions) = proposed_message(&ix);
+    assert_eq!(args.voting_deadline, 1_760_259_200);
+    assert_eq!(args.ephemeral_signers, 0);
+    assert_eq!(instructions.len(), 1);
+    let transfer = &instructions[0];
+    assert_eq!(transfer.program_id, spl_token::ID.to_string());
+    assert_eq!(
+        accounts(transfer),
+        vec![
+            (VAULT_TOKEN_ACCOUNT.to_string(), false, true),
+            (RECIPIENT_TOKEN_ACCOUNT.to_string(), false, true),
+            (VAULT.to_string(), true, true),
+            // The vault is also listed as the signer of the transfer.
+            (VAULT.to_string(), true, true),
+        ]
+    );
+    assert_eq!(
+        transfer.instruction.as_deref(),
+        Some("Transfer { amount: 250000000 }")
+    );
+}
+
+#[tokio::test]
+async fn voting_deadline_counts_from_the_cluster_clock() {
+    let sender = FixtureSender::load(&["clock", "multisig"]);
+    let (multisig, recipient) = (MULTISIG.to_string(), RECIPIENT.to_string());
+    for (deadline, expected) in [
+        (["--voting-deadline", "36h"], CLUSTER_TIME + 36 * 3_600),
+        (["--voting-deadline", "1d12h30m"], CLUSTER_TIME + 131_400),
+        (["--voting-period", "three-days"], CLUSTER_TIME + 3 * 86_400),
+        (
+            ["--voting-deadline", "2025-10-12T10:00:00+02:00"],
+            1_760_256_000,
+        ),
+    ] {
+        let mut args = vec![
+            "initiate-native-transfer",
+            "--multisig-pubkey",
+            &multisig,
+            "--recipient",
+            &recipient,
+            "--token-amount-u64",
+            "1000000000",
+        ];
+        args.extend(deadline);
+        dry_run(&sender, &args).await;
+
+        let (args, _) = proposed_message(&fortis_instruction(&sender, &[]));
+        assert_eq!(args.voting_deadline, expected, "{deadline:?}");
+    }
+}
+
+#[tokio::test]
+async fn voting_deadline_must_be_after_the_cluster_clock() {
+    let sender = FixtureSender::load(&["clock", "multisig"]);
+    let recipient = RECIPIENT.to_string();
+    let past = (CLUSTER_TIME - 60).to_string();
+    let member = member_keypair();
+    let err = fortis(
+        &sender,
+        &[
+            "initiate-native-transfer",
+            "--multisig-pubkey",
+            &MULTISIG.to_string(),
+            "--recipient",
+            &recipient,
+            "--token-amount-u64",
+            "1000000000",
+            "--voting-deadline",
+            &past,
+            "--keypair",
+            &member,
+            "--dry-run",
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("is not in the future"),
+        "unexpected error: {err}"
+    );
+    assert!(sender.transactions().is_empty());
+}
+
+#[tokio::test]
+async fn sign_only_proposal_needs_no_cluster() {
+    // No RPC URL and no RPC client: reading the multisig or the clock would fail the command.
+    let (multisig, recipient) = (MULTISIG.to_string(), RECIPIENT.to_string());
+    let deadline = (utils::now() + 3_600).to_string();
+    let member = member_keypair();
+    for deadline in ["3d", deadline.as_str()] {
+        fortis_offline(&[
+            "initiate-native-transfer",
+            "--multisig-pubkey",
+            &multisig,
+            "--recipient",
+            &recipient,
+            "--token-amount-u64",
+            "1000000000",
+            "--voting-deadline",
+            deadline,
+            "--transaction-index",
+            "4",
+            "--keypair",
+            &member,
+            "--sign-only",
+            "--blockhash",
+            "11111111111111111111111111111111",
+        ])
+        .await
+        .unwrap_or_else(|err| panic!("--voting-deadline {deadline}: {err:?}"));
+    }
+
+    let err = fortis_offline(&[
+        "initiate-native-transfer",
+        "--multisig-pubkey",
+        &multisig,
+        "--recipient",
+        &recipient,
+        "--token-amount-u64",
+        "1000000000",
+        "--voting-deadline",
+        "3d",
+        "--keypair",
+        &member,
+        "--sign-only",
+        "--blockhash",
+        "11111111111111111111111111111111",
+    ])
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("--transaction-index"),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test]
+async fn initiate_program_upgrade_proposes_buffer_handover_then_upgrade() {
+    let sender = FixtureSender::load(&["clock", "multisig"]);
+    let program = Pubkey::new_from_array([7; 32]);
+    let buffer = Pubkey::new_from_array([8; 32]);
+    let spill = Pubkey::new_from_array([9; 32]);
+    let (program_id, buffer_address, spill_address) =
+        (program.to_string(), buffer.to_string(), spill.to_string());
+    dry_run(
+        &sender,
+        &[
+            "initiate-program-upgrade",
+            "--multisig-pubkey",
+            &MULTISIG.to_string(),
+            "--program-to-upgrade-id",
+            &program_id,
+            "--buffer-address",
+            &buffer_address,
+            "--spill-address",
+            &spill_address,
+            "--voting-deadline",
+            VOTING_DEADLINE,
+        ],
+    )
+    .await;
+
+    let ix = fortis_instruction(&sender, &[]);
+    assert_creates_next_proposal(&ix);
+
+    let (_, instructions) = proposed_message(&ix);
+    let loader = solana_sdk_ids::bpf_loader_upgradeable::ID;
+    let program_data = Pubkey::find_program_address(&[program.as_ref()], &loader).0;
+    assert_eq!(instructions.len(), 2);
+
+    // The member who wrote the buffer hands it over to the vault...
+    let set_authority = &instructions[0];
+    assert_eq!(set_authority.program_id, loader.to_string());
+    assert_eq!(
+        accounts(set_authority),
+        vec![
+            (buffer.to_string(), false, true),
+            (MEMBER.to_string(), true, false),
+            (VAULT.to_string(), true, true),
+        ]
+    );
+
+    // ...which then upgrades the program from it.
+    let upgrade = &instructions[1];
+    assert_eq!(upgrade.program_id, loader.to_string());
+    assert_eq!(
+        accounts(upgrade),
+        vec![
+            (program_data.to_string(), false, true),
+            (program.to_string(), false, true),
+            (buffer.to_string(), false, true),
+            (spill.to_string(), false, true),
+            (solana_sdk_ids::sysvar::rent::ID.to_string(), false, false),
+            (solana_sdk_ids::sysvar::clock::ID.to_string(), false, false),
+            (VAULT.to_string(), true, true),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn proposal_execute_passes_the_vault_transaction_accounts() {
+    let sender = FixtureSender::load(&[
+        "clock",
+        "multisig",
+        "proposal",
+        "transaction",
+        "lookup_table",
+    ]);
+    dry_run(
+        &sender,
+        &[
+            "proposal-execute",
+            "--multisig-pubkey",
+            &MULTISIG.to_string(),
+            "--transaction-index",
+            "3",
+        ],
+    )
+    .await;
+
+    let lookup_table = AddressLookupTableAccount {
+        key: LOOKUP_TABLE,
+        addresses: vec![MINT, RECIPIENT_TOKEN_ACCOUNT],
+    };
+    let ix = fortis_instruction(&sender, &[lookup_table]);
+    assert_eq!(ix.data, vec![3]);
+    assert_eq!(
+        ix.accounts,
+        vec![
+            (MULTISIG, false, false),
+            (PROPOSAL, false, true),
+            (TRANSACTION, false, true),
+            (MEMBER, true, true),
+            // The lookup table, then the accounts of the vault transaction, with the ones it
+            // loads from the table last.
+            (LOOKUP_TABLE, false, false),
+            (VAULT, false, true),
+            (VAULT_TOKEN_ACCOUNT, false, true),
+            (spl_token::ID, false, false),
+            (RECIPIENT_TOKEN_ACCOUNT, false, true),
+            (MINT, false, false),
+        ]
+    );
+}
@@ -0,0 +1,114 @@
+//! Explanations of failed transactions: which program failed, found from the logs for a CPI or
+//! from the failing instruction otherwise, and what its error code means.
+
+use fortis_multisig_cli::program_error::explain;
+use fortis_sdk::pda::FORTIS_PROGRAM_ID;
+use solana_instruction::{error::InstructionError, AccountMeta, Instruction};
+use solana_message::{v0::Message, VersionedMessage};
+use solana_sdk::{hash::Hash, pubkey::Pubkey, transaction::TransactionError};
+
+/// A system transfer followed by a Fortis instruction and an upgradeable loader instruction.
+fn message() -> VersionedMessage {
+    let payer = Pubkey::new_from_array([1; 32]);
+    let program = |program_id| Instruction::new_with_bytes(program_id, &[], vec![]);
+    VersionedMessage::V0(
+        Message::try_compile(
+            &payer,
+            &[
+                solana_system_interface::instruction::transfer(
+                    &payer,
+                    &Pubkey::new_from_array([2; 32]),
+                    1,
+                ),
+                Instruction::new_with_bytes(
+                    FORTIS_PROGRAM_ID,
+                    &[3],
+                    vec![AccountMeta::new(payer, true)],
+                ),
+                program(solana_sdk_ids::bpf_loader_upgradeable::ID),
+            ],
+            &[],
+            Hash::default(),
+        )
+        .unwrap(),
+    )
+}
+
+fn explain_failure(index: u8, err: InstructionError, logs: &[String]) -> Option<String> {
+    explain(
+        &TransactionError::InstructionError(index, err),
+        &message(),
+        logs,
+        &FORTIS_PROGRAM_ID,
+    )
+}
+
+#[test]
+fn system_error_of_the_failing_instruction() {
+    assert_eq!(
+        explain_failure(0, InstructionError::Custom(1), &[]).as_deref(),
+        Some(
+            "System ResultWithNegativeLamports (0x1): account does not have enough SOL to \
+             perform the operation"
+        )
+    );
+    // Codes the program does not define are left alone.
+    assert_eq!(explain_failure(0, InstructionError::Custom(99), &[]), None);
+}
+
+#[test]
+fn token_error_of_a_cpi_is_found_in_the_logs() {
+    let logs = [
+        format!("Program {FORTIS_PROGRAM_ID} invoke [1]"),
+        format!("Program {} invoke [2]", spl_token_interface::ID),
+        format!(
+            "Program {} failed: custom program error: 0x1",
+            spl_token_interface::ID
+        ),
+        format!("Program {FORTIS_PROGRAM_ID} consumed 5000 of 200000 compute units"),
+    ];
+    assert_eq!(
+        explain_failure(1, InstructionError::Custom(1), &logs).as_deref(),
+        Some("SPL Token InsufficientFunds (0x1): Insufficient funds")
+    );
+}
+
+#[test]
+fn fortis_errors_keep_their_raw_code() {
+    assert_eq!(explain_failure(1, InstructionError::Custom(6), &[]), None);
+    let logs = [format!(
+        "Program {FORTIS_PROGRAM_ID} failed: custom program error: 0x6"
+    )];
+    assert_eq!(explain_failure(0, InstructionError::Custom(6), &logs), None);
+}
+
+#[test]
+fn loader_errors_are_standard_instruction_errors() {
+    assert_eq!(
+        explain_failure(2, InstructionError::IncorrectAuthority, &[]).as_deref(),
+        Some(
+            "BPF Upgradeable Loader IncorrectAuthority: the upgrade authority of the program or \
+             buffer must be the multisig vault"
+        )
+    );
+    // Standard errors of other programs say enough on their own.
+    assert_eq!(
+        explain_failure(0, InstructionError::IncorrectAuthority, &[]),
+        None
+    );
+}
+
+#[test]
+fn only_instruction_errors_are_explained() {
+    assert_eq!(
+        explain(
+            &TransactionError::BlockhashNotFound,
+            &message(),
+            &[],
+            &FORTIS_PROGRAM_ID
+        ),
+        None
+    );
+    // An instruction index outside the message.
+    assert_eq!(explain_failure(7, InstructionError::Custom(1), &[]), None);
+}
@@ -0,0 +1,631 @@
+//! Snapshots of what users and their scripts see: the review screen of every command that sends
+//! a transaction, the read-only views of the recorded accounts, and the `--help` of every
+//! command. They catch flags and output lines that change by accident.
+//!
+//! The binary runs against the fixture RPC with colors off. After an intended change, refresh
+//! the files in `tests/snapshots` and review the diff:
+//!
+//! ```text
+//! UPDATE_SNAPSHOTS=1 cargo test --test snapshots
+//! ```
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::process::Command as Process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use clap::CommandFactory;
+use common::*;
+use fortis_multisig_cli::command::Cli;
+use fortis_sdk::state::VaultTransactionMessage;
+use solana_message::{v0::Message, VersionedMessage};
+use solana_sdk::{
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, write_keypair_file, Keypair, Signature, Signer},
+};
+
+const VOTING_DEADLINE: &str = "1760259200";
+
+fn snapshot_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("tests/snapshots/{name}.txt"))
+}
+
+/// Compares `actual` with the snapshot `name`, or rewrites it when `UPDATE_SNAPSHOTS` is set.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Snapshot {} is missing, create it with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )
+    });
+    if expected != actual {
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "Snapshot {} differs from line {}. If the change is intended, run with \
+             UPDATE_SNAPSHOTS=1.\n\n--- expected\n{}\n--- actual\n{}",
+            path.display(),
+            line + 1,
+            expected,
+            actual
+        );
+    }
+}
+
+/// An empty home directory, holding the config file of the runs that share it.
+fn temp_home() -> PathBuf {
+    static HOMES: AtomicUsize = AtomicUsize::new(0);
+    let home = std::env::temp_dir().join(format!(
+        "fortis-snapshots-{}-{}",
+        std::process::id(),
+        HOMES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&home);
+    std::fs::create_dir_all(&home).unwrap();
+    home
+}
+
+/// Runs the binary against `url` without prompting, in a fresh home directory.
+fn run(url: &str, args: &[&str]) -> String {
+    run_in(&temp_home(), url, args)
+}
+
+/// Runs the binary against `url` without prompting and returns what it printed, stderr last.
+/// The RPC URL, which changes from run to run, is replaced. Deadlines count down from the
+/// fixture clock, so the time left is stable.
+fn run_in(home: &Path, url: &str, args: &[&str]) -> String {
+    let output = Process::new(env!("CARGO_BIN_EXE_fortis-multisig-cli"))
+        .arg("--config")
+        .arg(home.join("config.toml"))
+        .args(["--url", url, "--yes"])
+        .args(args)
+        .env("HOME", home)
+        .env("NO_COLOR", "1")
+        .env("TZ", "UTC")
+        .env("RUST_BACKTRACE", "0")
+        .env("RUST_LIB_BACKTRACE", "0")
+        .output()
+        .unwrap();
+
+    let mut printed = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    if !stderr.is_empty() {
+        // Errors end with the line they were raised at, which moves with every edit.
+        let stderr = stderr.split("\n\nLocation:").next().unwrap();
+        printed.push_str("--- stderr\n");
+        printed.push_str(stderr.trim_end());
+        printed.push('\n');
+    }
+
+    printed.replace(url, "<RPC_URL>")
+}
+
+/// Runs a transaction command as the fixture member with `--dry-run`, and a fixed compute
+/// budget so that only the transaction itself is simulated.
+fn dry_run(url: &str, args: &[&str]) -> String {
+    dry_run_in(&temp_home(), url, args)
+}
+
+fn dry_run_in(home: &Path, url: &str, args: &[&str]) -> String {
+    let member = member_keypair();
+    let mut args = args.to_vec();
+    args.extend([
+        "--keypair",
+        &member,
+        "--dry-run",
+        "--compute-unit-limit",
+        "200000",
+        "--priority-fee",
+        "0",
+    ]);
+    run_in(home, url, &args)
+}
+
+fn serve_accounts() -> String {
+    serve(FixtureSender::load(&[
+        "clock",
+        "multisig",
+        "proposal",
+        "transaction",
+        "lookup_table",
+    ]))
+}
+
+#[test]
+fn multisig_create() {
+    let url = serve_accounts();
+    let printed = dry_run(
+        &url,
+        &[
+            "multisig-create",
+            "--members",
+            &MEMBER.to_string(),
+            "--members",
+            &OTHER_MEMBER.to_string(),
+            "--threshold",
+            "2",
+            "--rent-collector",
+            &MEMBER.to_string(),
+        ],
+    );
+    assert_snapshot("multisig-create", &printed);
+}
+
+#[test]
+fn proposal_create() {
+    let url = serve_accounts();
+    let message = VaultTransactionMessage::try_compile(
+        &VAULT,
+        &[solana_system_interface::instruction::transfer(
+            &VAULT, &RECIPIENT, 1_000_000,
+        )],
+        &[],
+    )
+    .unwrap();
+    let message = bs58::encode(borsh::to_vec(&message).unwrap()).into_string();
+    assert_snapshot(
+        "proposal-create",
+        &dry_run(
+            &url,
+            &[
+                "proposal-create",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--transaction-message",
+                &message,
+                "--voting-deadline",
+                "2025-10-12T08:00:00Z",
+            ],
+        ),
+    );
+}
+
+#[test]
+fn proposal_create_malformed_message() {
+    let url = serve_accounts();
+    let message = || {
+        VaultTransactionMessage::try_compile(
+            &VAULT,
+            &[solana_system_interface::instruction::transfer(
+                &VAULT, &RECIPIENT, 1_000_000,
+            )],
+            &[],
+        )
+        .unwrap()
+    };
+    // An account index past the account keys, then more signers than account keys.
+    let mut out_of_range = message();
+    out_of_range.instructions[0].accounts = vec![0, 7];
+    let mut too_many_signers = message();
+    too_many_signers.num_signers = 5;
+
+    let mut printed = String::new();
+    for message in [out_of_range, too_many_signers] {
+        printed.push_str(&dry_run(
+            &url,
+            &[
+                "proposal-create",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--transaction-message",
+                &bs58::encode(borsh::to_vec(&message).unwrap()).into_string(),
+                "--voting-deadline",
+                "2025-10-12T08:00:00Z",
+            ],
+        ));
+    }
+    assert_snapshot("proposal-create-malformed-message", &printed);
+}
+
+#[test]
+fn initiate_transfer() {
+    let url = serve_accounts();
+    assert_snapshot(
+        "initiate-transfer",
+        &dry_run(
+            &url,
+            &[
+                "initiate-transfer",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--token-mint-address",
+                &MINT.to_string(),
+                "--recipient",
+                &RECIPIENT.to_string(),
+                "--token-amount-u64",
+                "250000000",
+                "--voting-deadline",
+                "3d",
+            ],
+        ),
+    );
+}
+
+#[test]
+fn initiate_native_transfer() {
+    let url = serve_accounts();
+    assert_snapshot(
+        "initiate-native-transfer",
+        &dry_run(
+            &url,
+            &[
+                "initiate-native-transfer",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--recipient",
+                &RECIPIENT.to_string(),
+                "--token-amount-u64",
+                "1000000000",
+                "--voting-period",
+                "week",
+            ],
+        ),
+    );
+}
+
+#[test]
+fn initiate_program_upgrade() {
+    let url = serve_accounts();
+    assert_snapshot(
+        "initiate-program-upgrade",
+        &dry_run(
+            &url,
+            &[
+                "initiate-program-upgrade",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--program-to-upgrade-id",
+                &Pubkey::new_from_array([7; 32]).to_string(),
+                "--buffer-address",
+                &Pubkey::new_from_array([8; 32]).to_string(),
+                "--spill-address",
+                &Pubkey::new_from_array([9; 32]).to_string(),
+                "--voting-deadline",
+                VOTING_DEADLINE,
+            ],
+        ),
+    );
+}
+
+#[test]
+fn proposal_approve() {
+    let url = serve_accounts();
+    // Proposal #3 is already approved, so the command stops after the review.
+    assert_snapshot(
+        "proposal-approve",
+        &dry_run(
+            &url,
+            &[
+                "proposal-approve",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--transaction-index",
+                "3",
+            ],
+        ),
+    );
+}
+
+#[test]
+fn proposal_execute() {
+    let url = serve_accounts();
+    assert_snapshot(
+        "proposal-execute",
+        &dry_run(
+            &url,
+            &[
+                "proposal-execute",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--transaction-index",
+                "3",
+            ],
+        ),
+    );
+}
+
+#[test]
+fn proposal_accounts_close() {
+    let url = serve_accounts();
+    assert_snapshot(
+        "proposal-accounts-close",
+        &dry_run(
+            &url,
+            &[
+                "proposal-accounts-close",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--transaction-index",
+                "3",
+                "--rent-collector",
+                &MEMBER.to_string(),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn nonce_create() {
+    let url = serve_accounts();
+    let nonce_keypair = std::env::temp_dir().join(format!(
+        "fortis-snapshots-nonce-{}.json",
+        std::process::id()
+    ));
+    write_keypair_file(&Keypair::new_from_array([10; 32]), &nonce_keypair).unwrap();
+    assert_snapshot(
+        "nonce-create",
+        &run(
+            &url,
+            &[
+                "nonce",
+                "create",
+                "--keypair",
+                &member_keypair(),
+                "--nonce-keypair",
+                nonce_keypair.to_str().unwrap(),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn broadcast() {
+    let url = serve_accounts();
+    let member = read_keypair_file(member_keypair()).unwrap();
+    let message = VersionedMessage::V0(
+        Message::try_compile(
+            &MEMBER,
+            &[solana_system_interface::instruction::transfer(
+                &MEMBER, &RECIPIENT, 1_000_000,
+            )],
+            &[],
+            Hash::new_from_array([1; 32]),
+        )
+        .unwrap(),
+    );
+    let message_bytes = message.serialize();
+    let signer = format!("{}={}", MEMBER, member.sign_message(&message_bytes));
+    assert_snapshot(
+        "broadcast",
+        &run(
+            &url,
+            &[
+                "broadcast",
+                "--message",
+                &bs58::encode(message_bytes).into_string(),
+                "--signer",
+                &signer,
+            ],
+        ),
+    );
+}
+
+#[test]
+fn broadcast_malformed_message() {
+    let url = serve_accounts();
+    let mut printed = String::new();
+    // More required signatures than account keys, then no signer at all.
+    for num_required_signatures in [5, 0] {
+        let mut message = Message::try_compile(
+            &MEMBER,
+            &[solana_system_interface::instruction::transfer(
+                &MEMBER, &RECIPIENT, 1_000_000,
+            )],
+            &[],
+            Hash::new_from_array([1; 32]),
+        )
+        .unwrap();
+        message.header.num_required_signatures = num_required_signatures;
+        message.header.num_readonly_signed_accounts = 0;
+        let message_bytes = VersionedMessage::V0(message).serialize();
+        printed.push_str(&run(
+            &url,
+            &[
+                "broadcast",
+                "--message",
+                &bs58::encode(message_bytes).into_string(),
+            ],
+        ));
+    }
+    assert_snapshot("broadcast-malformed-message", &printed);
+}
+
+#[test]
+fn pending_with_unreachable_status() {
+    // The first status check fails, the second transaction is still reported.
+    let url = serve(FixtureSender::load(&["clock", "multisig"]).failing("getSignatureStatuses", 1));
+    let home = temp_home();
+    let journal: String = [[1; 64], [2; 64]]
+        .iter()
+        .map(|signature| {
+            format!(
+                "{}\n",
+                serde_json::json!({
+                    "signature": Signature::from(*signature).to_string(),
+                    "command": "proposal-approve",
+                    "rpc_url": url,
+                    "multisig": MULTISIG.to_string(),
+                    "transaction_index": 3,
+                    "blockhash": Hash::new_from_array([1; 32]).to_string(),
+                    "sent_at": 1_760_100_000,
+                    "status": "pending",
+                })
+            )
+        })
+        .collect();
+    std::fs::write(home.join("journal.jsonl"), journal).unwrap();
+    assert_snapshot(
+        "pending-unreachable-status",
+        &run_in(&home, &url, &["pending"]),
+    );
+}
+
+#[test]
+fn display_multisig() {
+    let url = serve_accounts();
+    assert_snapshot(
+        "display-multisig",
+        &run(
+            &url,
+            &[
+                "display-multisig",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn display_vault_with_deprecated_flag() {
+    let url = serve_accounts();
+    assert_snapshot(
+        "display-vault-deprecated-flag",
+        &run(
+            &url,
+            &["display-vault", "--multisig-address", &MULTISIG.to_string()],
+        ),
+    );
+}
+
+/// Pubkey arguments may be address book labels or keypair files.
+#[test]
+fn unusable_solana_cli_config() {
+    let url = serve_accounts();
+    let mut printed = String::new();
+    // A legacy commitment is understood, an unknown one or a broken file is ignored.
+    for config in [
+        "commitment: singleGossip\n",
+        "commitment: fastest\n",
+        "json_rpc_url: [unterminated\n",
+    ] {
+        let home = temp_home();
+        let dir = home.join(".config/solana/cli");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("config.yml"), config).unwrap();
+        printed.push_str(&format!("$ config.yml: {}", config));
+        let output = run_in(
+            &home,
+            &url,
+            &["display-vault", "--multisig-pubkey", &MULTISIG.to_string()],
+        );
+        printed.push_str(&output.replace(home.to_str().unwrap(), "<HOME>"));
+    }
+    assert_snapshot("unusable-solana-cli-config", &printed);
+}
+
+#[test]
+fn address_book_labels() {
+    let url = serve_accounts();
+    let home = temp_home();
+    let mut printed = run_in(
+        &home,
+        &url,
+        &[
+            "config",
+            "address-book",
+            "set",
+            "treasury",
+            &MULTISIG.to_string(),
+        ],
+    );
+    printed.push_str(&dry_run_in(
+        &home,
+        &url,
+        &[
+            "initiate-native-transfer",
+            "--multisig-pubkey",
+            "treasury",
+            "--recipient",
+            &member_keypair(),
+            "--token-amount-u64",
+            "1000000000",
+            "--voting-deadline",
+            VOTING_DEADLINE,
+        ],
+    ));
+    printed.push_str(&run_in(
+        &home,
+        &url,
+        &["display-vault", "--multisig-pubkey", "payroll"],
+    ));
+    assert_snapshot("address-book-labels", &printed);
+}
+
+/// Malformed pubkeys are usage errors reported by clap.
+#[test]
+fn invalid_pubkeys() {
+    let url = serve_accounts();
+    let mut printed = String::new();
+    for recipient in [
+        "8SFqwqnq4whPhs8icwHA2hQg3hUoN1qrCLK1SBx3WK",
+        "missing/keypair.json",
+        "not a pubkey",
+    ] {
+        printed.push_str(&dry_run(
+            &url,
+            &[
+                "initiate-native-transfer",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--recipient",
+                recipient,
+                "--token-amount-u64",
+                "1000000000",
+                "--voting-deadline",
+                VOTING_DEADLINE,
+            ],
+        ));
+    }
+    assert_snapshot("invalid-pubkeys", &printed);
+}
+
+#[test]
+fn proposal_show() {
+    let url = serve_accounts();
+    assert_snapshot(
+        "proposal-show",
+        &run(
+            &url,
+            &[
+                "proposal-show",
+                "--multisig-pubkey",
+                &MULTISIG.to_string(),
+                "--transaction-index",
+                "3",
+            ],
+        ),
+    );
+}
+
+/// `--help` of the CLI and of every subcommand, depth first.
+#[test]
+fn help() {
+    fn render(command: &mut clap::Command, path: &str, help: &mut String) {
+        help.push_str(&format!("$ {path} --help\n"));
+        help.push_str(&command.render_long_help().to_string());
+        help.push('\n');
+        for subcommand in command.get_subcommands_mut() {
+            let path = format!("{path} {}", subcommand.get_name());
+            render(subcommand, &path, help);
+        }
+    }
+
+    let mut command = Cli::command();
+    command.build();
+    let mut help = String::new();
+    render(&mut command, "fortis-multisig-cli", &mut help);
+    assert_snapshot("help", &help);
+}